@@ -0,0 +1,268 @@
+//! Module permettant de placer un [`Cache`] devant un stockage plus lent.
+//!
+//! ## Fonctionnalités principales
+//! - Définir un stockage sous-jacent avec le trait [`Backend`].
+//! - Charger automatiquement une valeur depuis le stockage lors d'un défaut de cache
+//!   avec [`BackedCache::get`].
+//! - Écrire les modifications immédiatement ([`WriteMode::WriteThrough`]) ou
+//!   seulement lors de l'éviction ou d'un [`BackedCache::flush`] ([`WriteMode::WriteBack`]).
+//! - Utiliser un stockage en mémoire ([`MemoryBackend`]) ou un [`PersistentCache`].
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::io;
+
+use crate::cache_lru::Cache;
+use crate::storage::{Codec, PersistentCache};
+
+/// # Trait `Backend`
+///
+/// Ce trait représente un stockage de clés et de valeurs plus lent que le cache,
+/// par exemple un fichier, une base de données ou un service distant.
+pub trait Backend<K, V> {
+    /// Charge la valeur associée à une clé, ou `None` si elle n'existe pas.
+    fn load(&mut self, key: &K) -> io::Result<Option<V>>;
+
+    /// Enregistre une valeur pour une clé.
+    fn store(&mut self, key: &K, value: &V) -> io::Result<()>;
+
+    /// Supprime une clé du stockage.
+    fn delete(&mut self, key: &K) -> io::Result<()>;
+}
+
+/// # Structure `MemoryBackend`
+///
+/// Stockage en mémoire basé sur un `HashMap`, utile pour les tests.
+#[derive(Debug, Default)]
+pub struct MemoryBackend<K, V> {
+    map: HashMap<K, V>,
+}
+
+impl<K: Eq + Hash, V> MemoryBackend<K, V> {
+    /// ## Méthode `new`
+    ///
+    /// Crée un stockage en mémoire vide.
+    pub fn new() -> Self {
+        Self {
+            map: HashMap::new(),
+        }
+    }
+
+    /// ## Méthode `contains`
+    ///
+    /// Indique si une clé est présente dans le stockage.
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// ## Méthode `value`
+    ///
+    /// Récupère la valeur enregistrée pour une clé.
+    pub fn value(&self, key: &K) -> Option<&V> {
+        self.map.get(key)
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Backend<K, V> for MemoryBackend<K, V> {
+    fn load(&mut self, key: &K) -> io::Result<Option<V>> {
+        Ok(self.map.get(key).cloned())
+    }
+
+    fn store(&mut self, key: &K, value: &V) -> io::Result<()> {
+        self.map.insert(key.clone(), value.clone());
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &K) -> io::Result<()> {
+        self.map.remove(key);
+        Ok(())
+    }
+}
+
+// Adaptateur permettant d'utiliser un cache persistant comme stockage sous-jacent.
+impl<K, V, KC, VC> Backend<K, V> for PersistentCache<K, V, KC, VC>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    KC: Codec<K>,
+    VC: Codec<V>,
+{
+    fn load(&mut self, key: &K) -> io::Result<Option<V>> {
+        Ok(self.get(key).cloned())
+    }
+
+    fn store(&mut self, key: &K, value: &V) -> io::Result<()> {
+        Ok(self.put(key.clone(), value.clone())?)
+    }
+
+    fn delete(&mut self, key: &K) -> io::Result<()> {
+        self.remove(key)?;
+        Ok(())
+    }
+}
+
+/// # Enum `WriteMode`
+///
+/// Stratégie d'écriture vers le stockage sous-jacent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMode {
+    /// Chaque `put` est écrit immédiatement dans le stockage.
+    WriteThrough,
+    /// Les éléments modifiés sont écrits lors de leur éviction ou d'un `flush`.
+    WriteBack,
+}
+
+/// # Structure `BackedCache`
+///
+/// Cette structure place un cache LRU devant un [`Backend`]. Les défauts de cache
+/// sont chargés depuis le stockage, et les écritures y sont propagées selon le
+/// [`WriteMode`] choisi.
+///
+/// ## Types génériques
+/// - `K` : Le type des clés (doit être `Eq`, `Hash`, et `Clone`).
+/// - `V` : Le type des valeurs.
+/// - `B` : Le stockage sous-jacent.
+#[derive(Debug)]
+pub struct BackedCache<K, V, B> {
+    cache: Cache<K, V>,
+    backend: B,
+    mode: WriteMode,
+    dirty: HashSet<K>,
+}
+
+impl<K: Eq + Hash + Clone, V, B: Backend<K, V>> BackedCache<K, V, B> {
+    /// ## Méthode `new`
+    ///
+    /// Crée un cache d'une capacité donnée devant un stockage.
+    ///
+    /// ### Arguments
+    /// - `capacity` : La capacité maximale du cache.
+    /// - `backend` : Le stockage sous-jacent.
+    /// - `mode` : La stratégie d'écriture.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::backend::{BackedCache, MemoryBackend, WriteMode};
+    /// let cache: BackedCache<&str, i32, _> =
+    ///     BackedCache::new(2, MemoryBackend::new(), WriteMode::WriteThrough);
+    /// ```
+    pub fn new(capacity: usize, backend: B, mode: WriteMode) -> Self {
+        Self {
+            cache: Cache::new(capacity),
+            backend,
+            mode,
+            dirty: HashSet::new(),
+        }
+    }
+
+    /// ## Méthode `get`
+    ///
+    /// Récupère une valeur depuis le cache. En cas de défaut, la valeur est
+    /// chargée depuis le stockage puis ajoutée au cache.
+    ///
+    /// ### Retourne
+    /// - `Ok(Some(&V))` : La valeur, présente dans le cache ou chargée.
+    /// - `Ok(None)` : Si la clé n'existe pas dans le stockage.
+    /// - `Err(io::Error)` : Si le stockage renvoie une erreur.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::backend::{Backend, BackedCache, MemoryBackend, WriteMode};
+    /// let mut backend = MemoryBackend::new();
+    /// backend.store(&"A", &1).unwrap();
+    /// let mut cache = BackedCache::new(2, backend, WriteMode::WriteThrough);
+    /// assert_eq!(cache.get(&"A").unwrap(), Some(&1));
+    /// ```
+    pub fn get(&mut self, key: &K) -> io::Result<Option<&V>> {
+        if !self.cache.contains(key) {
+            match self.backend.load(key)? {
+                Some(value) => self.insert(key.clone(), value)?,
+                None => return Ok(None),
+            }
+        }
+        Ok(self.cache.get(key))
+    }
+
+    /// ## Méthode `put`
+    ///
+    /// Ajoute une clé et une valeur au cache. En mode [`WriteMode::WriteThrough`],
+    /// la valeur est écrite immédiatement dans le stockage ; en mode
+    /// [`WriteMode::WriteBack`], elle est marquée comme modifiée.
+    ///
+    /// ### Retourne
+    /// - `Ok(())` : Si l'ajout réussit.
+    /// - `Err(io::Error)` : Si l'écriture dans le stockage échoue.
+    pub fn put(&mut self, key: K, value: V) -> io::Result<()> {
+        if self.mode == WriteMode::WriteThrough {
+            self.backend.store(&key, &value)?;
+        }
+        self.insert(key.clone(), value)?;
+        if self.mode == WriteMode::WriteBack {
+            self.dirty.insert(key);
+        }
+        Ok(())
+    }
+
+    /// ## Méthode `remove`
+    ///
+    /// Retire une clé du cache et du stockage.
+    ///
+    /// ### Retourne
+    /// - `Ok(Some(V))` : La valeur retirée du cache.
+    /// - `Ok(None)` : Si la clé n'était pas dans le cache.
+    /// - `Err(io::Error)` : Si la suppression dans le stockage échoue.
+    pub fn remove(&mut self, key: &K) -> io::Result<Option<V>> {
+        self.backend.delete(key)?;
+        self.dirty.remove(key);
+        Ok(self.cache.remove(key))
+    }
+
+    /// ## Méthode `flush`
+    ///
+    /// Écrit dans le stockage tous les éléments modifiés encore présents dans le cache.
+    ///
+    /// ### Retourne
+    /// - `Ok(())` : Si toutes les écritures réussissent.
+    /// - `Err(io::Error)` : À la première erreur ; les éléments non écrits restent modifiés.
+    pub fn flush(&mut self) -> io::Result<()> {
+        let dirty: Vec<K> = self.dirty.iter().cloned().collect();
+        for key in dirty {
+            if let Some(value) = self.cache.peek(&key) {
+                self.backend.store(&key, value)?;
+            }
+            self.dirty.remove(&key);
+        }
+        Ok(())
+    }
+
+    /// ## Méthode `is_dirty`
+    ///
+    /// Indique si une clé a été modifiée sans être encore écrite dans le stockage.
+    pub fn is_dirty(&self, key: &K) -> bool {
+        self.dirty.contains(key)
+    }
+
+    /// ## Méthode `backend`
+    ///
+    /// Donne accès au stockage sous-jacent.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    // Ajoute un élément au cache. L'élément qui va être évincé est écrit dans le
+    // stockage avant d'être retiré s'il était modifié : en cas d'échec, il reste
+    // dans le cache, toujours marqué, et le nouvel élément n'est pas ajouté.
+    fn insert(&mut self, key: K, value: V) -> io::Result<()> {
+        if !self.cache.contains(&key) && self.cache.len() >= self.cache.capacity() {
+            if let Some((old_key, old_value)) = self.cache.peek_lru() {
+                if self.dirty.contains(old_key) {
+                    self.backend.store(old_key, old_value)?;
+                    let old_key = old_key.clone();
+                    self.dirty.remove(&old_key);
+                }
+            }
+        }
+        self.cache.push(key, value);
+        Ok(())
+    }
+}
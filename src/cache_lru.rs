@@ -6,13 +6,78 @@
 //! ## Fonctionnalités principales
 //! - Ajouter des éléments au cache avec la méthode [`Cache::put`].
 //! - Récupérer des éléments du cache avec la méthode [`Cache::get`].
+//! - Mémoriser les clés connues comme absentes avec la méthode [`Cache::put_absent`]
+//!   et les distinguer des clés inconnues avec la méthode [`Cache::lookup`].
+//! - Associer des tags aux éléments avec la méthode [`Cache::put_tagged`] et retirer
+//!   tous les éléments d'un tag avec la méthode [`Cache::invalidate_tag`].
+//! - Activer un index ordonné des clés avec la méthode [`Cache::enable_ordered_index`]
+//!   pour parcourir ou retirer des intervalles de clés ([`Cache::range`],
+//!   [`Cache::remove_range`], [`Cache::remove_prefix`]).
+//! - Exporter et importer les éléments aux formats JSON Lines et CSV avec les
+//!   méthodes [`Cache::export`] et [`Cache::import`].
 
-use std::collections::HashMap;
+use std::borrow::Borrow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::ops::{Bound, RangeBounds};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::exchange::{self, ExchangeFormat, ImportReport, Overflow};
+use crate::storage::StorageError;
+
+/// # Enum `Lookup`
+///
+/// Résultat d'une recherche avec [`Cache::lookup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup<T> {
+    /// La clé est présente dans le cache.
+    Present(T),
+    /// La clé est connue comme absente de la source.
+    Absent,
+    /// Le cache ne sait rien de cette clé.
+    Unknown,
+}
+
+// Élément du cache, chaîné à ses voisins dans l'ordre d'accès.
+#[derive(Debug)]
+struct Node<K, V> {
+    value: V,
+    tags: Vec<String>,
+    // Clé de l'élément utilisé juste après celui-ci.
+    prev: Option<K>,
+    // Clé de l'élément utilisé juste avant celui-ci.
+    next: Option<K>,
+}
+
+// Index ordonné des clés, utilisé pour les intervalles et les préfixes, tenu à jour
+// à chaque ajout et retrait d'un élément. Le cache n'exige pas `K: Ord` : les
+// fonctions de mise à jour sont fixées à l'activation de l'index, où il est connu.
+struct OrderedIndex<K> {
+    keys: BTreeSet<K>,
+    insert: fn(&mut BTreeSet<K>, &K),
+    remove: fn(&mut BTreeSet<K>, &K),
+}
+
+impl<K> fmt::Debug for OrderedIndex<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrderedIndex")
+            .field("len", &self.keys.len())
+            .finish()
+    }
+}
 
 /// # Structure `Cache`
 ///
 /// Cette structure représente un cache LRU générique.
-/// Elle utilise un `HashMap` pour stocker les données et un `Vec` pour maintenir l'ordre d'accès.
+/// Elle utilise un `HashMap` pour stocker les données, chaque élément étant chaîné
+/// à ses voisins pour maintenir l'ordre d'accès : déplacer ou retirer un élément se
+/// fait en temps constant. Un index des tags permet de retirer un groupe d'éléments
+/// en un temps proportionnel à la taille du groupe.
+/// Les clés connues comme absentes sont conservées à part, avec leur propre durée de
+/// vie et leur propre capacité. Un index ordonné optionnel permet de parcourir ou de
+/// retirer des intervalles de clés sans parcourir tout le cache.
 ///
 /// ## Types génériques
 /// - `K` : Le type des clés (doit être `Eq`, `Hash`, et `Clone`).
@@ -20,8 +85,17 @@ use std::collections::HashMap;
 #[derive(Debug)]
 pub struct Cache<K, V> {
     capacity: usize,
-    map: HashMap<K, V>,
-    keys: Vec<K>,
+    map: HashMap<K, Node<K, V>>,
+    // Élément le plus récemment utilisé.
+    head: Option<K>,
+    // Élément le moins récemment utilisé.
+    tail: Option<K>,
+    tags: HashMap<String, HashSet<K>>,
+    index: Option<OrderedIndex<K>>,
+    absent: HashMap<K, Instant>,
+    absent_keys: Vec<K>,
+    absent_capacity: usize,
+    absent_ttl: Duration,
 }
 
 impl<K: Eq + std::hash::Hash + Clone, V> Cache<K, V> {
@@ -34,13 +108,48 @@ impl<K: Eq + std::hash::Hash + Clone, V> Cache<K, V> {
     ///
     /// ### Exemple
     /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
     /// let cache: Cache<&str, String> = Cache::new(3);
     /// ```
     pub fn new(capacity: usize) -> Self {
         Self {
             capacity,
             map: HashMap::new(),
-            keys: Vec::new(),
+            head: None,
+            tail: None,
+            tags: HashMap::new(),
+            index: None,
+            absent: HashMap::new(),
+            absent_keys: Vec::new(),
+            absent_capacity: 0,
+            absent_ttl: Duration::ZERO,
+        }
+    }
+
+    /// ## Méthode `set_negative_caching`
+    ///
+    /// Active la mémorisation des clés connues comme absentes de la source.
+    /// Ces marqueurs ont leur propre capacité, distincte de celle du cache, et
+    /// leur propre durée de vie. Une capacité de `0` désactive la fonctionnalité.
+    ///
+    /// ### Arguments
+    /// - `ttl` : La durée pendant laquelle une clé reste connue comme absente.
+    /// - `capacity` : Le nombre maximal de clés connues comme absentes.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// # use std::time::Duration;
+    /// let mut cache: Cache<&str, String> = Cache::new(3);
+    /// cache.set_negative_caching(Duration::from_secs(10), 2);
+    /// ```
+    pub fn set_negative_caching(&mut self, ttl: Duration, capacity: usize) {
+        self.absent_ttl = ttl;
+        self.absent_capacity = capacity;
+        while self.absent_keys.len() > capacity {
+            if let Some(old_key) = self.absent_keys.pop() {
+                self.absent.remove(&old_key);
+            }
         }
     }
 
@@ -56,19 +165,168 @@ impl<K: Eq + std::hash::Hash + Clone, V> Cache<K, V> {
     ///
     /// ### Exemple
     /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
     /// let mut cache = Cache::new(2);
     /// cache.put("A", "Valeur A".to_string());
     /// ```
     pub fn put(&mut self, key: K, value: V) {
-        if self.map.contains_key(&key) {
-            self.keys.retain(|k| k != &key);
-        } else if self.keys.len() >= self.capacity {
-            if let Some(old_key) = self.keys.pop() {
-                self.map.remove(&old_key);
+        self.push(key, value);
+    }
+
+    /// ## Méthode `push`
+    ///
+    /// Identique à [`Cache::put`], mais retourne l'élément retiré du cache
+    /// lorsque la capacité est atteinte.
+    ///
+    /// ### Arguments
+    /// - `key` : La clé associée à la valeur.
+    /// - `value` : La valeur à stocker.
+    ///
+    /// ### Retourne
+    /// - `Some((K, V))` : Le plus ancien élément, s'il a été retiré.
+    /// - `None` : Si aucun élément n'a été retiré.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache = Cache::new(1);
+    /// cache.push("A", 1);
+    /// assert_eq!(cache.push("B", 2), Some(("A", 1)));
+    /// ```
+    pub fn push(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.insert(key, value, Vec::new())
+    }
+
+    /// ## Méthode `put_tagged`
+    ///
+    /// Identique à [`Cache::put`], mais associe un ensemble de tags à l'élément.
+    /// Les tags remplacent ceux d'un éventuel élément précédent de même clé.
+    ///
+    /// ### Arguments
+    /// - `key` : La clé associée à la valeur.
+    /// - `value` : La valeur à stocker.
+    /// - `tags` : Les tags associés à l'élément.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache = Cache::new(3);
+    /// cache.put_tagged("user:42:profile", "Profil".to_string(), &["user:42"]);
+    /// ```
+    pub fn put_tagged(&mut self, key: K, value: V, tags: &[&str]) {
+        self.push_tagged(key, value, tags);
+    }
+
+    /// ## Méthode `push_tagged`
+    ///
+    /// Identique à [`Cache::put_tagged`], mais retourne l'élément retiré du cache
+    /// lorsque la capacité est atteinte.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache = Cache::new(1);
+    /// cache.push_tagged("A", 1, &["groupe"]);
+    /// assert_eq!(cache.push_tagged("B", 2, &["groupe"]), Some(("A", 1)));
+    /// ```
+    pub fn push_tagged(&mut self, key: K, value: V, tags: &[&str]) -> Option<(K, V)> {
+        let mut unique: Vec<String> = Vec::new();
+        for tag in tags {
+            if !unique.iter().any(|t| t == tag) {
+                unique.push(tag.to_string());
             }
         }
-        self.keys.insert(0, key.clone());
-        self.map.insert(key, value);
+        self.insert(key, value, unique)
+    }
+
+    /// ## Méthode `invalidate_tag`
+    ///
+    /// Retire tous les éléments portant un tag, en un temps proportionnel au
+    /// nombre d'éléments concernés.
+    ///
+    /// ### Arguments
+    /// - `tag` : Le tag des éléments à retirer.
+    ///
+    /// ### Retourne
+    /// Le nombre d'éléments retirés.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache = Cache::new(3);
+    /// cache.put_tagged("A", 1, &["groupe"]);
+    /// cache.put_tagged("B", 2, &["groupe"]);
+    /// cache.put("C", 3);
+    /// assert_eq!(cache.invalidate_tag("groupe"), 2);
+    /// assert_eq!(cache.get(&"C"), Some(&3));
+    /// ```
+    pub fn invalidate_tag(&mut self, tag: &str) -> usize {
+        let keys = self.tags.remove(tag).unwrap_or_default();
+        keys.iter().filter(|key| self.take(key).is_some()).count()
+    }
+
+    /// ## Méthode `tagged`
+    ///
+    /// Parcourt les clés des éléments portant un tag, sans modifier l'ordre d'accès.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache = Cache::new(3);
+    /// cache.put_tagged("A", 1, &["groupe"]);
+    /// cache.put("B", 2);
+    /// assert_eq!(cache.tagged("groupe").collect::<Vec<_>>(), vec![&"A"]);
+    /// ```
+    pub fn tagged(&self, tag: &str) -> impl Iterator<Item = &K> {
+        self.tags.get(tag).into_iter().flatten()
+    }
+
+    /// ## Méthode `tags`
+    ///
+    /// Récupère les tags associés à une clé.
+    ///
+    /// ### Retourne
+    /// - `Some(&[String])` : Les tags de l'élément s'il est présent.
+    /// - `None` : Si la clé n'est pas présente dans le cache.
+    pub fn tags(&self, key: &K) -> Option<&[String]> {
+        self.map.get(key).map(|node| node.tags.as_slice())
+    }
+
+    /// ## Méthode `pop_lru`
+    ///
+    /// Retire l'élément le moins récemment utilisé.
+    ///
+    /// ### Retourne
+    /// - `Some((K, V))` : L'élément retiré.
+    /// - `None` : Si le cache est vide.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let key = self.tail.clone()?;
+        let node = self.take(&key)?;
+        Some((key, node.value))
+    }
+
+    /// ## Méthode `peek_lru`
+    ///
+    /// Retourne l'élément le moins récemment utilisé, sans le retirer ni modifier
+    /// l'ordre d'accès.
+    ///
+    /// ### Retourne
+    /// - `Some((&K, &V))` : L'élément qui serait évincé au prochain ajout.
+    /// - `None` : Si le cache est vide.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache = Cache::new(2);
+    /// cache.put("A", 1);
+    /// cache.put("B", 2);
+    /// assert_eq!(cache.peek_lru(), Some((&"A", &1)));
+    /// ```
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        let key = self.tail.as_ref()?;
+        self.map
+            .get_key_value(key)
+            .map(|(key, node)| (key, &node.value))
     }
 
     /// ## Méthode `get`
@@ -85,6 +343,7 @@ impl<K: Eq + std::hash::Hash + Clone, V> Cache<K, V> {
     ///
     /// ### Exemple
     /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
     /// let mut cache = Cache::new(2);
     /// cache.put("A", "Valeur A".to_string());
     /// if let Some(value) = cache.get(&"A") {
@@ -93,11 +352,464 @@ impl<K: Eq + std::hash::Hash + Clone, V> Cache<K, V> {
     /// ```
     pub fn get(&mut self, key: &K) -> Option<&V> {
         if self.map.contains_key(key) {
-            self.keys.retain(|k| k != key);
-            self.keys.insert(0, key.clone());
-            self.map.get(key)
-        } else {
-            None
+            self.unlink(key);
+            self.link_front(key);
         }
+        self.map.get(key).map(|node| &node.value)
+    }
+
+    /// ## Méthode `peek`
+    ///
+    /// Récupère une valeur à partir de sa clé sans modifier l'ordre d'accès.
+    ///
+    /// ### Arguments
+    /// - `key` : La clé à rechercher dans le cache.
+    ///
+    /// ### Retourne
+    /// - `Some(&V)` : La valeur liée à la clé si elle est présente.
+    /// - `None` : Si la clé n'est pas présente dans le cache.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.map.get(key).map(|node| &node.value)
+    }
+
+    /// ## Méthode `peek_mut`
+    ///
+    /// Identique à [`Cache::peek`], mais retourne une référence modifiable.
+    pub fn peek_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.map.get_mut(key).map(|node| &mut node.value)
+    }
+
+    /// ## Méthode `remove`
+    ///
+    /// Retire une clé du cache.
+    ///
+    /// ### Arguments
+    /// - `key` : La clé à retirer.
+    ///
+    /// ### Retourne
+    /// - `Some(V)` : La valeur retirée.
+    /// - `None` : Si la clé n'est pas présente dans le cache.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache = Cache::new(2);
+    /// cache.put("A", 1);
+    /// assert_eq!(cache.remove(&"A"), Some(1));
+    /// assert_eq!(cache.get(&"A"), None);
+    /// ```
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.clear_absent(key);
+        self.take(key).map(|node| node.value)
+    }
+
+    /// ## Méthode `contains`
+    ///
+    /// Indique si une clé est présente dans le cache, sans modifier l'ordre d'accès.
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// ## Méthode `iter`
+    ///
+    /// Parcourt les éléments du plus récemment au moins récemment utilisé, sans
+    /// modifier l'ordre d'accès.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache = Cache::new(3);
+    /// cache.put("A", 1);
+    /// cache.put("B", 2);
+    /// let keys: Vec<_> = cache.iter().map(|(key, _)| *key).collect();
+    /// assert_eq!(keys, vec!["B", "A"]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut current = self.head.as_ref();
+        std::iter::from_fn(move || {
+            let (key, node) = self.map.get_key_value(current?)?;
+            current = node.next.as_ref();
+            Some((key, &node.value))
+        })
+    }
+
+    /// ## Méthode `export`
+    ///
+    /// Écrit les éléments du cache, du plus récemment au moins récemment utilisé,
+    /// au format JSON Lines ou CSV décrit dans le module [`exchange`]. L'ordre
+    /// d'utilisation n'est pas modifié.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// # use cache_lru::exchange::ExchangeFormat;
+    /// let mut cache = Cache::new(3);
+    /// cache.put("A", "dit \"bonjour\"");
+    /// let mut out = Vec::new();
+    /// cache.export(&mut out, ExchangeFormat::JsonLines).unwrap();
+    /// assert_eq!(out, b"{\"key\":\"A\",\"value\":\"dit \\\"bonjour\\\"\"}\n");
+    /// ```
+    pub fn export(&self, writer: impl Write, format: ExchangeFormat) -> io::Result<()>
+    where
+        K: fmt::Display,
+        V: fmt::Display,
+    {
+        exchange::write_entries(writer, format, self.iter())
+    }
+
+    /// ## Méthode `import`
+    ///
+    /// Ajoute au cache les éléments d'un fichier JSON Lines ou CSV, listés du plus
+    /// récemment au moins récemment utilisé. Ils deviennent les plus récents du
+    /// cache et gardent leur ordre. Si le fichier contient plus d'éléments que le
+    /// cache ne peut en recevoir, `overflow` choisit lesquels sont importés.
+    ///
+    /// ### Retourne
+    /// - `Ok(ImportReport)` : Le nombre d'éléments importés et ignorés.
+    /// - `Err(StorageError)` : Si la lecture échoue ou si une ligne est invalide ;
+    ///   rien n'est alors importé.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// # use cache_lru::exchange::{ExchangeFormat, Overflow};
+    /// let mut cache: Cache<String, u32> = Cache::new(2);
+    /// let csv = "key,value\r\nc,3\r\nb,2\r\na,1\r\n";
+    /// let report = cache
+    ///     .import(csv.as_bytes(), ExchangeFormat::Csv, Overflow::KeepMostRecent(2))
+    ///     .unwrap();
+    /// assert_eq!((report.imported, report.dropped), (2, 1));
+    /// let keys: Vec<_> = cache.iter().map(|(key, _)| key.as_str()).collect();
+    /// assert_eq!(keys, vec!["c", "b"]);
+    /// ```
+    pub fn import(
+        &mut self,
+        reader: impl Read,
+        format: ExchangeFormat,
+        overflow: Overflow,
+    ) -> Result<ImportReport, StorageError>
+    where
+        K: FromStr,
+        V: FromStr,
+        <K as FromStr>::Err: fmt::Debug,
+        <V as FromStr>::Err: fmt::Debug,
+    {
+        let entries = exchange::read_entries(reader, format)?;
+        let (entries, dropped) = exchange::select(entries, overflow, self.capacity, self.len());
+        let imported = entries.len();
+        for (key, value) in entries {
+            self.put(key, value);
+        }
+        Ok(ImportReport { imported, dropped })
+    }
+
+    /// ## Méthode `capacity`
+    ///
+    /// Retourne la capacité maximale du cache.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// ## Méthode `len`
+    ///
+    /// Retourne le nombre d'éléments présents dans le cache.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// ## Méthode `is_empty`
+    ///
+    /// Indique si le cache est vide.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// ## Méthode `put_absent`
+    ///
+    /// Mémorise qu'une clé n'existe pas dans la source. La clé est retirée du cache
+    /// si elle y était présente. Le marqueur est effacé par le prochain [`Cache::put`]
+    /// de cette clé, ou expire après la durée définie avec
+    /// [`Cache::set_negative_caching`]. Si la capacité des marqueurs est atteinte,
+    /// le plus ancien est retiré.
+    ///
+    /// ### Arguments
+    /// - `key` : La clé absente de la source.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::{Cache, Lookup};
+    /// # use std::time::Duration;
+    /// let mut cache: Cache<&str, String> = Cache::new(3);
+    /// cache.set_negative_caching(Duration::from_secs(10), 2);
+    /// cache.put_absent("A");
+    /// assert_eq!(cache.lookup(&"A"), Lookup::Absent);
+    /// ```
+    pub fn put_absent(&mut self, key: K) {
+        if self.absent_capacity == 0 {
+            return;
+        }
+        self.take(&key);
+        if self.absent.contains_key(&key) {
+            self.absent_keys.retain(|k| k != &key);
+        } else if self.absent_keys.len() >= self.absent_capacity {
+            if let Some(old_key) = self.absent_keys.pop() {
+                self.absent.remove(&old_key);
+            }
+        }
+        self.absent_keys.insert(0, key.clone());
+        self.absent.insert(key, Instant::now() + self.absent_ttl);
+    }
+
+    /// ## Méthode `lookup`
+    ///
+    /// Recherche une clé en distinguant les clés présentes, les clés connues comme
+    /// absentes et les clés inconnues. Une clé présente est marquée comme récemment
+    /// utilisée.
+    ///
+    /// ### Arguments
+    /// - `key` : La clé à rechercher dans le cache.
+    ///
+    /// ### Retourne
+    /// - `Lookup::Present(&V)` : La valeur liée à la clé si elle est présente.
+    /// - `Lookup::Absent` : Si la clé est connue comme absente et que le marqueur n'a pas expiré.
+    /// - `Lookup::Unknown` : Sinon.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::{Cache, Lookup};
+    /// let mut cache = Cache::new(2);
+    /// cache.put("A", 1);
+    /// assert_eq!(cache.lookup(&"A"), Lookup::Present(&1));
+    /// assert_eq!(cache.lookup(&"B"), Lookup::Unknown);
+    /// ```
+    pub fn lookup(&mut self, key: &K) -> Lookup<&V> {
+        match self.absent.get(key) {
+            Some(deadline) if Instant::now() < *deadline => return Lookup::Absent,
+            Some(_) => self.clear_absent(key),
+            None => {}
+        }
+        match self.get(key) {
+            Some(value) => Lookup::Present(value),
+            None => Lookup::Unknown,
+        }
+    }
+
+    // Ajoute un élément en tête de l'ordre d'accès et retourne l'élément évincé.
+    fn insert(&mut self, key: K, value: V, tags: Vec<String>) -> Option<(K, V)> {
+        self.clear_absent(&key);
+        let mut evicted = None;
+        if self.map.contains_key(&key) {
+            self.take(&key);
+        } else if self.map.len() >= self.capacity {
+            evicted = self.pop_lru();
+        }
+        for tag in &tags {
+            self.tags
+                .entry(tag.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+        let node = Node {
+            value,
+            tags,
+            prev: None,
+            next: None,
+        };
+        if let Some(index) = &mut self.index {
+            (index.insert)(&mut index.keys, &key);
+        }
+        self.map.insert(key.clone(), node);
+        self.link_front(&key);
+        evicted
+    }
+
+    // Retire un élément du cache, de l'ordre d'accès et de l'index des tags.
+    fn take(&mut self, key: &K) -> Option<Node<K, V>> {
+        if !self.map.contains_key(key) {
+            return None;
+        }
+        self.unlink(key);
+        if let Some(index) = &mut self.index {
+            (index.remove)(&mut index.keys, key);
+        }
+        let node = self.map.remove(key)?;
+        for tag in &node.tags {
+            if let Some(keys) = self.tags.get_mut(tag) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.tags.remove(tag);
+                }
+            }
+        }
+        Some(node)
+    }
+
+    // Détache un élément de ses voisins dans l'ordre d'accès.
+    fn unlink(&mut self, key: &K) {
+        let Some(node) = self.map.get_mut(key) else {
+            return;
+        };
+        let prev = node.prev.take();
+        let next = node.next.take();
+        match &prev {
+            Some(prev_key) => {
+                if let Some(prev_node) = self.map.get_mut(prev_key) {
+                    prev_node.next = next.clone();
+                }
+            }
+            None => self.head = next.clone(),
+        }
+        match &next {
+            Some(next_key) => {
+                if let Some(next_node) = self.map.get_mut(next_key) {
+                    next_node.prev = prev;
+                }
+            }
+            None => self.tail = prev,
+        }
+    }
+
+    // Place un élément détaché en tête de l'ordre d'accès.
+    fn link_front(&mut self, key: &K) {
+        let old_head = self.head.replace(key.clone());
+        match &old_head {
+            Some(head_key) => {
+                if let Some(head_node) = self.map.get_mut(head_key) {
+                    head_node.prev = Some(key.clone());
+                }
+            }
+            None => self.tail = Some(key.clone()),
+        }
+        if let Some(node) = self.map.get_mut(key) {
+            node.next = old_head;
+        }
+    }
+
+    // Efface le marqueur d'absence d'une clé.
+    fn clear_absent(&mut self, key: &K) {
+        if self.absent.remove(key).is_some() {
+            self.absent_keys.retain(|k| k != key);
+        }
+    }
+}
+
+impl<K: Ord + std::hash::Hash + Clone, V> Cache<K, V> {
+    /// ## Méthode `enable_ordered_index`
+    ///
+    /// Active un index ordonné des clés, tenu à jour lors des ajouts, des mises à
+    /// jour et des évictions. Il permet à [`Cache::range`], [`Cache::remove_range`] et
+    /// [`Cache::remove_prefix`] de ne parcourir que les clés concernées.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache: Cache<String, u32> = Cache::new(100);
+    /// cache.enable_ordered_index();
+    /// ```
+    pub fn enable_ordered_index(&mut self) {
+        if self.index.is_none() {
+            self.index = Some(OrderedIndex {
+                keys: self.map.keys().cloned().collect(),
+                insert: |keys, key| {
+                    keys.insert(key.clone());
+                },
+                remove: |keys, key| {
+                    keys.remove(key);
+                },
+            });
+        }
+    }
+
+    /// ## Méthode `range`
+    ///
+    /// Parcourt, dans l'ordre des clés, les éléments dont la clé appartient à un
+    /// intervalle, sans modifier l'ordre d'accès. Sans index ordonné, toutes les clés
+    /// sont parcourues puis triées.
+    ///
+    /// ### Arguments
+    /// - `range` : L'intervalle des clés à parcourir.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache = Cache::new(3);
+    /// cache.enable_ordered_index();
+    /// cache.put(1, "un");
+    /// cache.put(3, "trois");
+    /// cache.put(2, "deux");
+    /// let values: Vec<_> = cache.range(2..).map(|(_, value)| *value).collect();
+    /// assert_eq!(values, vec!["deux", "trois"]);
+    /// ```
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl Iterator<Item = (&K, &V)> {
+        let keys: Vec<&K> = match self.ordered_keys() {
+            Some(ordered) => ordered.range(range).collect(),
+            None => {
+                let mut keys: Vec<&K> = self.map.keys().filter(|k| range.contains(*k)).collect();
+                keys.sort();
+                keys
+            }
+        };
+        keys.into_iter()
+            .filter_map(|key| self.map.get(key).map(|node| (key, &node.value)))
+    }
+
+    /// ## Méthode `remove_range`
+    ///
+    /// Retire tous les éléments dont la clé appartient à un intervalle.
+    ///
+    /// ### Arguments
+    /// - `range` : L'intervalle des clés à retirer.
+    ///
+    /// ### Retourne
+    /// Le nombre d'éléments retirés.
+    pub fn remove_range<R: RangeBounds<K>>(&mut self, range: R) -> usize {
+        let keys: Vec<K> = self.range(range).map(|(key, _)| key.clone()).collect();
+        keys.iter().filter(|key| self.take(key).is_some()).count()
+    }
+
+    /// ## Méthode `remove_prefix`
+    ///
+    /// Retire tous les éléments dont la clé commence par un préfixe, par exemple
+    /// `user:42:` pour retirer `user:42:*`.
+    ///
+    /// ### Arguments
+    /// - `prefix` : Le préfixe des clés à retirer.
+    ///
+    /// ### Retourne
+    /// Le nombre d'éléments retirés.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::cache_lru::Cache;
+    /// let mut cache = Cache::new(3);
+    /// cache.enable_ordered_index();
+    /// cache.put("user:42:profile".to_string(), 1);
+    /// cache.put("user:42:posts".to_string(), 2);
+    /// cache.put("user:7:profile".to_string(), 3);
+    /// assert_eq!(cache.remove_prefix("user:42:"), 2);
+    /// ```
+    pub fn remove_prefix(&mut self, prefix: &str) -> usize
+    where
+        K: Borrow<str>,
+    {
+        let keys: Vec<K> = match self.ordered_keys() {
+            Some(ordered) => ordered
+                .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+                .take_while(|key| (*key).borrow().starts_with(prefix))
+                .cloned()
+                .collect(),
+            None => self
+                .map
+                .keys()
+                .filter(|key| (*key).borrow().starts_with(prefix))
+                .cloned()
+                .collect(),
+        };
+        keys.iter().filter(|key| self.take(key).is_some()).count()
+    }
+
+    // Récupère l'index ordonné des clés s'il est activé.
+    fn ordered_keys(&self) -> Option<&BTreeSet<K>> {
+        self.index.as_ref().map(|index| &index.keys)
     }
 }
@@ -0,0 +1,466 @@
+//! Module d'import et d'export des éléments d'un cache, pour les consulter ou les
+//! préparer avec d'autres outils.
+//!
+//! Les éléments sont écrits du plus récemment au moins récemment utilisé, sous leur
+//! forme textuelle (`Display`), et relus avec `FromStr`. Deux formats sont pris en
+//! charge ([`ExchangeFormat`]) :
+//!
+//! - JSON Lines : un objet par ligne, dont les champs `key` et `value` sont des
+//!   chaînes JSON échappées. À l'import, les autres champs sont ignorés et une
+//!   valeur peut aussi être un nombre, un booléen ou `null`, relu tel quel.
+//!
+//! ```text
+//! {"key":"B","value":"ligne 1\nligne 2"}
+//! {"key":"A","value":"dit \"bonjour\""}
+//! ```
+//!
+//! - CSV (RFC 4180) : une ligne d'en-tête `key,value`, puis une ligne par élément,
+//!   terminées par `\r\n`. Un champ contenant une virgule, un guillemet ou un retour
+//!   à la ligne est entouré de guillemets, ses guillemets étant doublés. À l'import,
+//!   l'en-tête est facultatif et les lignes peuvent se terminer par `\n`.
+//!
+//! ```text
+//! key,value
+//! B,"ligne 1
+//! ligne 2"
+//! A,"dit ""bonjour"""
+//! ```
+//!
+//! Lorsque le fichier contient plus d'éléments que le cache ne peut en recevoir,
+//! [`Overflow`] choisit lesquels sont importés.
+
+use std::fmt::{Debug, Display};
+use std::io::{self, Read, Write};
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+
+use crate::storage::{BadLine, StorageError};
+
+/// # Enum `ExchangeFormat`
+///
+/// Format d'import et d'export des éléments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExchangeFormat {
+    /// Un objet JSON par ligne.
+    #[default]
+    JsonLines,
+    /// Valeurs séparées par des virgules, selon la RFC 4180.
+    Csv,
+}
+
+/// # Enum `Overflow`
+///
+/// Traitement des éléments importés au-delà de la capacité du cache. Le fichier
+/// liste les éléments du plus récent au plus ancien : les éléments conservés sont
+/// toujours les premiers du fichier, et gardent leur ordre d'utilisation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Seuls les éléments qui tiennent dans la place libre du cache sont importés :
+    /// aucun élément déjà présent n'est retiré.
+    #[default]
+    Truncate,
+    /// Les `N` éléments les plus récents du fichier, au plus la capacité du cache,
+    /// sont importés, en retirant si besoin les éléments déjà présents les moins
+    /// récemment utilisés.
+    KeepMostRecent(usize),
+}
+
+/// # Structure `ImportReport`
+///
+/// Résultat d'un import réussi.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Nombre d'éléments importés.
+    pub imported: usize,
+    /// Nombre d'éléments du fichier ignorés faute de place.
+    pub dropped: usize,
+}
+
+/// ## Fonction `write_entries`
+///
+/// Écrit des éléments, déjà dans l'ordre d'utilisation, dans le format choisi.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::exchange::{write_entries, ExchangeFormat};
+/// let mut out = Vec::new();
+/// write_entries(&mut out, ExchangeFormat::Csv, [("a,b", "1")]).unwrap();
+/// assert_eq!(out, b"key,value\r\n\"a,b\",1\r\n");
+/// ```
+pub fn write_entries<K, V>(
+    mut writer: impl Write,
+    format: ExchangeFormat,
+    entries: impl IntoIterator<Item = (K, V)>,
+) -> io::Result<()>
+where
+    K: Display,
+    V: Display,
+{
+    if format == ExchangeFormat::Csv {
+        writer.write_all(b"key,value\r\n")?;
+    }
+    for (key, value) in entries {
+        let (key, value) = (key.to_string(), value.to_string());
+        match format {
+            ExchangeFormat::JsonLines => writeln!(
+                writer,
+                "{{\"key\":{},\"value\":{}}}",
+                json_string(&key),
+                json_string(&value)
+            )?,
+            ExchangeFormat::Csv => write!(writer, "{},{}\r\n", csv_field(&key), csv_field(&value))?,
+        }
+    }
+    writer.flush()
+}
+
+/// ## Fonction `read_entries`
+///
+/// Relit tous les éléments d'un fichier, dans l'ordre du fichier.
+///
+/// ### Retourne
+/// - `Ok(Vec<(K, V)>)` : Les éléments relus.
+/// - `Err(StorageError::Io)` : Si la lecture échoue ou si le contenu n'est pas de
+///   l'UTF-8.
+/// - `Err(StorageError::Parse)` : Si une ligne est mal formée, ou si une clé ou une
+///   valeur ne peut pas être relue.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::exchange::{read_entries, ExchangeFormat};
+/// let input = "{\"key\":\"a\",\"value\":42}\n{\"key\":\"b\\u00e9\",\"value\":\"7\"}\n";
+/// let entries: Vec<(String, u32)> =
+///     read_entries(input.as_bytes(), ExchangeFormat::JsonLines).unwrap();
+/// assert_eq!(entries, vec![("a".to_string(), 42), ("bé".to_string(), 7)]);
+/// ```
+pub fn read_entries<K, V>(
+    mut reader: impl Read,
+    format: ExchangeFormat,
+) -> Result<Vec<(K, V)>, StorageError>
+where
+    K: FromStr,
+    V: FromStr,
+    <K as FromStr>::Err: Debug,
+    <V as FromStr>::Err: Debug,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    let records = match format {
+        ExchangeFormat::JsonLines => read_json_lines(&content)?,
+        ExchangeFormat::Csv => read_csv(&content)?,
+    };
+    records
+        .into_iter()
+        .map(|record| {
+            let key = record.key.parse::<K>();
+            let value = record.value.parse::<V>();
+            match (key, value) {
+                (Ok(key), Ok(value)) => Ok((key, value)),
+                (Err(err), _) => Err(record.bad(format!("clé invalide : {:?}", err))),
+                (_, Err(err)) => Err(record.bad(format!("valeur invalide : {:?}", err))),
+            }
+        })
+        .collect()
+}
+
+/// ## Fonction `select`
+///
+/// Choisit, selon `overflow`, les éléments importés dans un cache de capacité
+/// `capacity` qui contient déjà `len` éléments.
+///
+/// ### Retourne
+/// Les éléments à insérer, dans l'ordre d'insertion (du plus ancien au plus
+/// récent), et le nombre d'éléments ignorés.
+pub fn select<T>(
+    mut entries: Vec<T>,
+    overflow: Overflow,
+    capacity: usize,
+    len: usize,
+) -> (Vec<T>, usize) {
+    let kept = match overflow {
+        Overflow::Truncate => capacity.saturating_sub(len),
+        Overflow::KeepMostRecent(count) => count.min(capacity),
+    };
+    let dropped = entries.len().saturating_sub(kept);
+    entries.truncate(kept);
+    entries.reverse();
+    (entries, dropped)
+}
+
+// Élément relu, avec sa position dans le fichier pour signaler une erreur.
+struct Record {
+    line: usize,
+    text: String,
+    key: String,
+    value: String,
+}
+
+impl Record {
+    fn bad(&self, reason: String) -> StorageError {
+        bad_line(self.line, &self.text, reason)
+    }
+}
+
+fn bad_line(line: usize, text: &str, reason: String) -> StorageError {
+    StorageError::Parse(BadLine {
+        line,
+        text: text.to_string(),
+        reason,
+    })
+}
+
+// Chaîne JSON entre guillemets, avec ses caractères spéciaux échappés.
+fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn read_json_lines(content: &str) -> Result<Vec<Record>, StorageError> {
+    let mut records = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (key, value) =
+            parse_json_object(line).map_err(|reason| bad_line(index + 1, line, reason))?;
+        records.push(Record {
+            line: index + 1,
+            text: line.to_string(),
+            key,
+            value,
+        });
+    }
+    Ok(records)
+}
+
+// Lit un objet JSON sur une ligne et retourne ses champs `key` et `value`.
+fn parse_json_object(line: &str) -> Result<(String, String), String> {
+    let mut parser = JsonParser {
+        chars: line.char_indices().peekable(),
+        text: line,
+    };
+    let (mut key, mut value) = (None, None);
+    parser.expect('{')?;
+    if !parser.eat('}') {
+        loop {
+            let name = parser.string()?;
+            parser.expect(':')?;
+            let field = parser.value()?;
+            match name.as_str() {
+                "key" => key = Some(field),
+                "value" => value = Some(field),
+                _ => {}
+            }
+            if parser.eat('}') {
+                break;
+            }
+            parser.expect(',')?;
+        }
+    }
+    parser.skip_whitespace();
+    if parser.chars.peek().is_some() {
+        return Err("texte après l'objet".to_string());
+    }
+    Ok((
+        key.ok_or("champ \"key\" absent")?,
+        value.ok_or("champ \"value\" absent")?,
+    ))
+}
+
+struct JsonParser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    text: &'a str,
+}
+
+impl JsonParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if(|&(_, c)| c == expected).is_some()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(format!("'{}' attendu", expected))
+        }
+    }
+
+    // Valeur d'un champ : une chaîne, ou un nombre, un booléen ou `null` relu tel quel.
+    fn value(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some((_, '"')) => self.string(),
+            Some((_, '{' | '[')) => Err("valeur imbriquée non prise en charge".to_string()),
+            Some(&(start, _)) => {
+                let mut end = start;
+                while let Some((i, c)) = self
+                    .chars
+                    .next_if(|&(_, c)| c != ',' && c != '}' && !c.is_whitespace())
+                {
+                    end = i + c.len_utf8();
+                }
+                Ok(self.text[start..end].to_string())
+            }
+            None => Err("valeur attendue".to_string()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            let (_, c) = self.chars.next().ok_or("chaîne non terminée")?;
+            match c {
+                '"' => return Ok(string),
+                '\\' => {
+                    let (_, escape) = self.chars.next().ok_or("chaîne non terminée")?;
+                    string.push(match escape {
+                        '"' => '"',
+                        '\\' => '\\',
+                        '/' => '/',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => self.unicode()?,
+                        other => return Err(format!("échappement invalide : \\{}", other)),
+                    });
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    // Caractère `\uXXXX`, éventuellement suivi de la seconde moitié d'une paire de
+    // substitution.
+    fn unicode(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !(self.eat('\\') && self.eat('u')) {
+                return Err("paire de substitution incomplète".to_string());
+            }
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err("paire de substitution invalide".to_string());
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| format!("caractère invalide : {:x}", code))
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        (0..4).try_fold(0, |code, _| {
+            let digit = self
+                .chars
+                .next()
+                .and_then(|(_, c)| c.to_digit(16))
+                .ok_or("échappement \\u invalide")?;
+            Ok(code * 16 + digit)
+        })
+    }
+}
+
+// Champ CSV, entre guillemets s'il contient un caractère spécial.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn read_csv(content: &str) -> Result<Vec<Record>, StorageError> {
+    let mut records = Vec::new();
+    let mut chars = content.char_indices().peekable();
+    let mut line = 1;
+    while let Some(&(start, _)) = chars.peek() {
+        let first_line = line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut end = content.len();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' if quoted => {
+                    if chars.next_if(|&(_, c)| c == '"').is_some() {
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                }
+                '"' if field.is_empty() => quoted = true,
+                '\n' if quoted => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ if quoted => field.push(c),
+                ',' => fields.push(std::mem::take(&mut field)),
+                '\r' if chars.peek().is_some_and(|&(_, c)| c == '\n') => {}
+                '\n' => {
+                    line += 1;
+                    end = i;
+                    break;
+                }
+                '"' => {
+                    let text = content[start..].lines().next().unwrap_or_default();
+                    return Err(bad_line(
+                        first_line,
+                        text,
+                        "guillemet inattendu".to_string(),
+                    ));
+                }
+                c => field.push(c),
+            }
+        }
+        fields.push(field);
+        let text = content[start..end].trim_end_matches('\r');
+        if quoted {
+            return Err(bad_line(
+                first_line,
+                text,
+                "guillemet non fermé".to_string(),
+            ));
+        }
+        if text.is_empty() {
+            continue;
+        }
+        let [key, value]: [String; 2] = fields.try_into().map_err(|fields: Vec<String>| {
+            bad_line(
+                first_line,
+                text,
+                format!("2 champs attendus, {} trouvés", fields.len()),
+            )
+        })?;
+        if records.is_empty() && first_line == 1 && key == "key" && value == "value" {
+            continue;
+        }
+        records.push(Record {
+            line: first_line,
+            text: text.to_string(),
+            key,
+            value,
+        });
+    }
+    Ok(records)
+}
@@ -0,0 +1,513 @@
+//! Module implémentant un cache hybride : l'index en mémoire, les grandes valeurs sur disque.
+//!
+//! Les clés et l'ordre d'utilisation restent dans un [`Cache`]. Les valeurs dont la
+//! forme encodée dépasse un seuil sont écrites à la fin d'un segment sur disque, et
+//! le cache ne garde que leur position : la mémoire ne contient que les petites
+//! valeurs. Une valeur sur disque est relue à chaque [`HybridCache::get`].
+//!
+//! Un segment est un fichier `segment-N` du dossier du cache. Il reçoit des valeurs
+//! jusqu'à atteindre sa taille maximale, puis un nouveau segment est commencé. Les
+//! valeurs remplacées ou retirées restent dans leur segment jusqu'à sa compaction.
+//! Un dossier n'est utilisé que par un seul cache à la fois : il est verrouillé par
+//! un fichier `lock` tant que le cache existe.
+//!
+//! ## Fonctionnalités principales
+//! - Ajouter et récupérer des éléments avec [`HybridCache::put`] et [`HybridCache::get`].
+//! - Récupérer l'espace des valeurs mortes avec [`HybridCache::compact`].
+//! - Limiter l'espace disque avec [`HybridCache::set_disk_budget`] : les valeurs sur
+//!   disque les moins récemment utilisées sont retirées au-delà du budget.
+//! - Supprimer les segments laissés par une exécution précédente avec
+//!   [`HybridCache::remove_orphans`].
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::hash::Hash;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::cache_lru::Cache;
+use crate::storage::binary::crc32;
+use crate::storage::{Codec, StorageError, StringCodec};
+
+// Position d'une valeur dans un segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Location {
+    segment: u64,
+    offset: u64,
+    len: u64,
+    crc: u32,
+}
+
+// Valeur d'un élément : gardée en mémoire, ou écrite dans un segment.
+#[derive(Debug)]
+enum Slot<V> {
+    Memory(V),
+    Disk(Location),
+}
+
+// Segment ouvert, avec la taille de son fichier et celle de ses valeurs mortes.
+#[derive(Debug)]
+struct Segment {
+    file: File,
+    size: u64,
+    dead: u64,
+}
+
+/// # Structure `HybridCache`
+///
+/// Cette structure représente un cache LRU dont l'index est en mémoire et dont les
+/// grandes valeurs sont stockées dans des segments sur disque. Le contenu n'est pas
+/// conservé d'une exécution à l'autre : pour cela, utiliser un
+/// [`PersistentCache`](crate::storage::PersistentCache).
+///
+/// ## Types génériques
+/// - `K` : Type des clés (doit être `Eq`, `Hash`, et `Clone`).
+/// - `V` : Type des valeurs (doit être `Clone`).
+/// - `VC` : Conversion des valeurs en octets ([`StringCodec`] par défaut).
+#[derive(Debug)]
+pub struct HybridCache<K, V, VC = StringCodec> {
+    index: Cache<K, Slot<V>>,
+    value_codec: VC,
+    dir: PathBuf,
+    spill_threshold: usize,
+    segment_size: u64,
+    disk_budget: Option<u64>,
+    segments: BTreeMap<u64, Segment>,
+    // Somme des tailles des valeurs vivantes sur disque.
+    live_bytes: u64,
+    // Verrou exclusif du dossier, libéré à la destruction du cache.
+    _lock: File,
+}
+
+impl<K, V> HybridCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    StringCodec: Codec<V>,
+{
+    /// ## Méthode `open`
+    ///
+    /// Crée un cache hybride vide dont les valeurs sont écrites sous leur forme
+    /// textuelle. Le dossier est créé s'il n'existe pas, puis verrouillé, et les
+    /// segments d'une exécution précédente sont supprimés.
+    ///
+    /// ### Arguments
+    /// - `capacity` : Capacité maximale du cache, en nombre d'éléments.
+    /// - `dir` : Dossier des segments.
+    ///
+    /// ### Retourne
+    /// - `Ok(HybridCache)` : Le cache vide.
+    /// - `Err(StorageError::Io)` : Si le dossier ne peut pas être créé ou nettoyé,
+    ///   ou s'il est déjà utilisé par un autre cache (`io::ErrorKind::WouldBlock`).
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::hybrid::HybridCache;
+    /// let cache: HybridCache<String, String> = HybridCache::open(1000, "segments").unwrap();
+    /// ```
+    pub fn open(capacity: usize, dir: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::open_with_codec(capacity, dir, StringCodec)
+    }
+}
+
+impl<K, V, VC> HybridCache<K, V, VC>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    VC: Codec<V>,
+{
+    /// ## Méthode `open_with_codec`
+    ///
+    /// Identique à [`HybridCache::open`], avec la conversion en octets des valeurs.
+    /// Par défaut, les valeurs de plus de 1 Kio sont écrites sur disque, dans des
+    /// segments de 16 Mio au plus, sans budget d'espace disque.
+    pub fn open_with_codec(
+        capacity: usize,
+        dir: impl AsRef<Path>,
+        value_codec: VC,
+    ) -> Result<Self, StorageError> {
+        fs::create_dir_all(dir.as_ref())?;
+        let lock = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(dir.as_ref().join("lock"))?;
+        lock.try_lock().map_err(|err| match err {
+            TryLockError::WouldBlock => io::Error::new(
+                io::ErrorKind::WouldBlock,
+                "dossier déjà utilisé par un autre cache",
+            ),
+            TryLockError::Error(err) => err,
+        })?;
+        let mut cache = Self {
+            index: Cache::new(capacity),
+            value_codec,
+            dir: dir.as_ref().to_path_buf(),
+            spill_threshold: 1024,
+            segment_size: 16 * 1024 * 1024,
+            disk_budget: None,
+            segments: BTreeMap::new(),
+            live_bytes: 0,
+            _lock: lock,
+        };
+        cache.remove_orphans()?;
+        Ok(cache)
+    }
+
+    /// ## Méthode `set_spill_threshold`
+    ///
+    /// Définit la taille encodée, en octets, au-delà de laquelle une valeur est
+    /// écrite sur disque. S'applique aux prochains ajouts.
+    pub fn set_spill_threshold(&mut self, bytes: usize) {
+        self.spill_threshold = bytes;
+    }
+
+    /// ## Méthode `set_segment_size`
+    ///
+    /// Définit la taille à partir de laquelle un nouveau segment est commencé.
+    pub fn set_segment_size(&mut self, bytes: u64) {
+        self.segment_size = bytes.max(1);
+    }
+
+    /// ## Méthode `set_disk_budget`
+    ///
+    /// Limite l'espace disque des segments. Au-delà, les éléments sur disque les
+    /// moins récemment utilisés sont retirés du cache, puis les segments sont
+    /// compactés si leurs fichiers dépassent encore le budget.
+    ///
+    /// ### Arguments
+    /// - `budget` : Le nombre maximal d'octets, ou `None` pour ne pas limiter.
+    ///
+    /// ### Retourne
+    /// - `Ok(())` : Si le budget est respecté.
+    /// - `Err(StorageError::Io)` : Si la compaction échoue.
+    pub fn set_disk_budget(&mut self, budget: Option<u64>) -> Result<(), StorageError> {
+        self.disk_budget = budget;
+        self.enforce_budget()
+    }
+
+    /// ## Méthode `put`
+    ///
+    /// Ajoute une clé et une valeur au cache. La valeur est écrite sur disque si sa
+    /// forme encodée dépasse le seuil, et gardée en mémoire sinon. Si la capacité
+    /// est atteinte, le plus ancien élément est supprimé.
+    ///
+    /// ### Retourne
+    /// - `Ok(())` : Si l'ajout réussit.
+    /// - `Err(StorageError::Io)` : Si l'écriture sur disque échoue ; la clé n'est
+    ///   alors plus dans le cache.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::hybrid::HybridCache;
+    /// # let mut cache: HybridCache<String, String> = HybridCache::open(1000, "segments").unwrap();
+    /// cache.put("A".to_string(), "Valeur A".to_string()).unwrap();
+    /// ```
+    pub fn put(&mut self, key: K, value: V) -> Result<(), StorageError> {
+        self.remove(&key)?;
+        let encoded = self.value_codec.encode(&value);
+        let slot = if encoded.len() > self.spill_threshold {
+            Slot::Disk(self.write_value(&encoded)?)
+        } else {
+            Slot::Memory(value)
+        };
+        let spilled = matches!(slot, Slot::Disk(_));
+        if let Some((_, evicted)) = self.index.push(key, slot) {
+            self.release(evicted)?;
+        }
+        if spilled {
+            self.enforce_budget()?;
+        }
+        Ok(())
+    }
+
+    /// ## Méthode `get`
+    ///
+    /// Récupère une valeur depuis sa clé, en la relisant sur disque si besoin. Si la
+    /// clé est trouvée, elle est marquée comme récemment utilisée.
+    ///
+    /// ### Retourne
+    /// - `Ok(Some(V))` : Une copie de la valeur si elle est trouvée.
+    /// - `Ok(None)` : Si la clé n'existe pas dans le cache.
+    /// - `Err(StorageError::Io)` : Si la valeur ne peut pas être relue, ou si elle
+    ///   est corrompue.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::hybrid::HybridCache;
+    /// # let mut cache: HybridCache<String, String> = HybridCache::open(1000, "segments").unwrap();
+    /// if let Some(value) = cache.get(&"A".to_string()).unwrap() {
+    ///     println!("Valeur : {}", value);
+    /// }
+    /// ```
+    pub fn get(&mut self, key: &K) -> Result<Option<V>, StorageError> {
+        let location = match self.index.get(key) {
+            None => return Ok(None),
+            Some(Slot::Memory(value)) => return Ok(Some(value.clone())),
+            Some(Slot::Disk(location)) => *location,
+        };
+        let bytes = self.read_value(location)?;
+        let value = self.value_codec.decode(&bytes).map_err(|reason| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("valeur invalide : {}", reason),
+            )
+        })?;
+        Ok(Some(value))
+    }
+
+    /// ## Méthode `remove`
+    ///
+    /// Retire une clé du cache. Sa valeur sur disque devient morte.
+    ///
+    /// ### Retourne
+    /// - `Ok(true)` : Si la clé était présente.
+    /// - `Ok(false)` : Si la clé n'existe pas dans le cache.
+    /// - `Err(StorageError::Io)` : Si un segment vide n'a pas pu être supprimé.
+    pub fn remove(&mut self, key: &K) -> Result<bool, StorageError> {
+        match self.index.remove(key) {
+            Some(slot) => {
+                self.release(slot)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// ## Méthode `contains`
+    ///
+    /// Indique si une clé est présente dans le cache, sans modifier l'ordre d'accès.
+    pub fn contains(&self, key: &K) -> bool {
+        self.index.contains(key)
+    }
+
+    /// ## Méthode `is_on_disk`
+    ///
+    /// Indique si la valeur d'une clé est stockée sur disque.
+    pub fn is_on_disk(&self, key: &K) -> bool {
+        matches!(self.index.peek(key), Some(Slot::Disk(_)))
+    }
+
+    /// ## Méthode `len`
+    ///
+    /// Retourne le nombre d'éléments présents dans le cache.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// ## Méthode `is_empty`
+    ///
+    /// Indique si le cache est vide.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// ## Méthode `disk_usage`
+    ///
+    /// Retourne la taille totale des segments, valeurs mortes comprises.
+    pub fn disk_usage(&self) -> u64 {
+        self.segments.values().map(|segment| segment.size).sum()
+    }
+
+    /// ## Méthode `live_disk_bytes`
+    ///
+    /// Retourne la taille des valeurs sur disque encore utilisées par le cache.
+    pub fn live_disk_bytes(&self) -> u64 {
+        self.live_bytes
+    }
+
+    /// ## Méthode `compact`
+    ///
+    /// Réécrit les valeurs vivantes des segments contenant des valeurs mortes à la
+    /// fin du segment courant, puis supprime ces segments. L'ordre d'utilisation
+    /// n'est pas modifié.
+    ///
+    /// ### Retourne
+    /// - `Ok(())` : Si la compaction réussit.
+    /// - `Err(StorageError::Io)` : Si une lecture ou une écriture échoue ; les
+    ///   valeurs déjà déplacées restent valides.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::hybrid::HybridCache;
+    /// # let mut cache: HybridCache<String, String> = HybridCache::open(1000, "segments").unwrap();
+    /// cache.compact().unwrap();
+    /// ```
+    pub fn compact(&mut self) -> Result<(), StorageError> {
+        // Le segment courant est compacté lui aussi : on en commence un nouveau.
+        let targets: Vec<u64> = self
+            .segments
+            .iter()
+            .filter(|(_, segment)| segment.dead > 0)
+            .map(|(&id, _)| id)
+            .collect();
+        if targets.is_empty() {
+            return Ok(());
+        }
+        self.create_segment()?;
+
+        let moved: Vec<(K, Location)> = self
+            .index
+            .iter()
+            .filter_map(|(key, slot)| match slot {
+                Slot::Disk(location) if targets.contains(&location.segment) => {
+                    Some((key.clone(), *location))
+                }
+                _ => None,
+            })
+            .collect();
+        for (key, location) in moved {
+            let bytes = self.read_value(location)?;
+            let new_location = self.write_value(&bytes)?;
+            self.live_bytes -= location.len;
+            if let Some(slot) = self.index.peek_mut(&key) {
+                *slot = Slot::Disk(new_location);
+            }
+        }
+        for id in targets {
+            self.segments.remove(&id);
+            fs::remove_file(self.segment_path(id))?;
+        }
+        Ok(())
+    }
+
+    /// ## Méthode `remove_orphans`
+    ///
+    /// Supprime les fichiers de segment du dossier qui ne sont pas utilisés par ce
+    /// cache, par exemple ceux d'une exécution précédente.
+    ///
+    /// ### Retourne
+    /// - `Ok(usize)` : Le nombre de fichiers supprimés.
+    /// - `Err(StorageError::Io)` : Si le dossier ne peut pas être lu ou nettoyé.
+    pub fn remove_orphans(&mut self) -> Result<usize, StorageError> {
+        let mut removed = 0;
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let id = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("segment-"))
+                .and_then(|id| id.parse::<u64>().ok());
+            if id.is_some_and(|id| !self.segments.contains_key(&id)) {
+                fs::remove_file(entry.path())?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    // Retire des éléments sur disque, du moins au plus récemment utilisé, tant que
+    // les valeurs vivantes dépassent le budget, puis compacte si les fichiers le
+    // dépassent encore.
+    fn enforce_budget(&mut self) -> Result<(), StorageError> {
+        let Some(budget) = self.disk_budget else {
+            return Ok(());
+        };
+        if self.live_bytes > budget {
+            let mut on_disk: Vec<K> = self
+                .index
+                .iter()
+                .filter(|(_, slot)| matches!(slot, Slot::Disk(_)))
+                .map(|(key, _)| key.clone())
+                .collect();
+            while self.live_bytes > budget {
+                let Some(key) = on_disk.pop() else { break };
+                self.remove(&key)?;
+            }
+        }
+        if self.disk_usage() > budget {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    // Écrit une valeur à la fin du segment courant, commencé s'il est plein.
+    fn write_value(&mut self, bytes: &[u8]) -> Result<Location, StorageError> {
+        let full = self.segments.last_key_value().is_none_or(|(_, segment)| {
+            segment.size > 0 && segment.size + bytes.len() as u64 > self.segment_size
+        });
+        if full {
+            self.create_segment()?;
+        }
+        let mut current = self
+            .segments
+            .last_entry()
+            .expect("un segment courant existe");
+        let id = *current.key();
+        let segment = current.get_mut();
+        if let Err(err) = segment.file.write_all(bytes) {
+            // Une écriture partielle décalerait les valeurs suivantes du segment.
+            let _ = segment.file.set_len(segment.size);
+            return Err(err.into());
+        }
+        let location = Location {
+            segment: id,
+            offset: segment.size,
+            len: bytes.len() as u64,
+            crc: crc32(bytes),
+        };
+        segment.size += location.len;
+        self.live_bytes += location.len;
+        Ok(location)
+    }
+
+    fn read_value(&mut self, location: Location) -> Result<Vec<u8>, StorageError> {
+        let segment = self
+            .segments
+            .get_mut(&location.segment)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "segment introuvable"))?;
+        let mut bytes = vec![0; location.len as usize];
+        segment.file.seek(SeekFrom::Start(location.offset))?;
+        segment.file.read_exact(&mut bytes)?;
+        if crc32(&bytes) != location.crc {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("valeur corrompue dans le segment {}", location.segment),
+            )
+            .into());
+        }
+        Ok(bytes)
+    }
+
+    fn create_segment(&mut self) -> Result<(), StorageError> {
+        let id = self.segments.last_key_value().map_or(0, |(&id, _)| id + 1);
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .truncate(false)
+            .open(self.segment_path(id))?;
+        file.set_len(0)?;
+        self.segments.insert(
+            id,
+            Segment {
+                file,
+                size: 0,
+                dead: 0,
+            },
+        );
+        Ok(())
+    }
+
+    // Marque la valeur d'un élément retiré comme morte. Un segment plein dont toutes
+    // les valeurs sont mortes est supprimé.
+    fn release(&mut self, slot: Slot<V>) -> Result<(), StorageError> {
+        let Slot::Disk(location) = slot else {
+            return Ok(());
+        };
+        self.live_bytes -= location.len;
+        let current = self.segments.last_key_value().map(|(&id, _)| id);
+        if let Some(segment) = self.segments.get_mut(&location.segment) {
+            segment.dead += location.len;
+            if segment.dead == segment.size && current != Some(location.segment) {
+                self.segments.remove(&location.segment);
+                fs::remove_file(self.segment_path(location.segment))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn segment_path(&self, id: u64) -> PathBuf {
+        self.dir.join(format!("segment-{}", id))
+    }
+}
@@ -5,3 +5,17 @@
 pub mod cache_lru;
 // Module implémentant un cache LRU avec persistance.
 pub mod storage;
+// Module permettant de placer un cache devant un stockage plus lent.
+pub mod backend;
+// Module implémentant un cache à chargement automatique avec rafraîchissement anticipé.
+pub mod refresh;
+// Module regroupant les statistiques d'utilisation d'un cache.
+pub mod stats;
+// Module implémentant un cache partagé entre plusieurs espaces de noms.
+pub mod partition;
+// Module implémentant un cache à deux niveaux, en mémoire puis persistant.
+pub mod tiered;
+// Module implémentant un cache dont les grandes valeurs sont stockées sur disque.
+pub mod hybrid;
+// Module d'import et d'export des éléments d'un cache aux formats JSON Lines et CSV.
+pub mod exchange;
@@ -9,11 +9,8 @@
 // - Initialisation d'un cache non persistant, ajout et récupération de données.
 // - Initialisation d'un cache persistant, ajout, sauvegarde, et rechargement depuis un fichier.
 
-mod cache_lru;
-mod storage;
-
-use crate::cache_lru::Cache;
-use crate::storage::PersistentCache;
+use cache_lru::cache_lru::Cache;
+use cache_lru::storage::PersistentCache;
 
 fn main() {
     println!("--------------------------------------------------------- Utilisation du Cache LRU  ---------------------------------------------------------");
@@ -57,11 +54,17 @@ fn main() {
     println!("On initialise le cache LRU avec une capacité de 3");
 
     // On ajoute des données au cache
-    persistent_cache.put("E".to_string(), "Valeur E".to_string());
+    persistent_cache
+        .put("E".to_string(), "Valeur E".to_string())
+        .unwrap();
     println!("E a été Ajouté avec pour valeur : Valeur E");
-    persistent_cache.put("F".to_string(), "Valeur F".to_string());
+    persistent_cache
+        .put("F".to_string(), "Valeur F".to_string())
+        .unwrap();
     println!("F a été Ajouté avec pour valeur : Valeur F");
-    persistent_cache.put("G".to_string(), "Valeur G".to_string());
+    persistent_cache
+        .put("G".to_string(), "Valeur G".to_string())
+        .unwrap();
     println!("G a été Ajouté avec pour valeur : Valeur G");
 
     // On sauvegarde le contenu du cache dans un fichier.txt
@@ -79,7 +82,9 @@ fn main() {
     }
 
     // On ajoute une 4ème valeur dans le cache
-    persistent_cache.put("H".to_string(), "Valeur H".to_string());
+    persistent_cache
+        .put("H".to_string(), "Valeur H".to_string())
+        .unwrap();
     println!("Ajouté : H -> Valeur H, la valeur F a été supprimé du cache");
 
     // On remplis le cache avec le contenu du fichier txt
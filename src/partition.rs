@@ -0,0 +1,323 @@
+//! Module implémentant un cache LRU partagé entre plusieurs espaces de noms.
+//!
+//! Chaque espace de noms (par exemple un client) dispose de sa propre partition,
+//! avec une part minimale garantie et une part maximale de la capacité totale.
+//! Lorsque la capacité totale est atteinte, l'élément retiré est pris dans la
+//! partition qui dépasse le plus sa part équitable.
+//!
+//! ## Fonctionnalités principales
+//! - Ajouter et récupérer des éléments dans un espace de noms avec
+//!   [`PartitionedCache::put`] et [`PartitionedCache::get`].
+//! - Définir les parts d'un espace de noms avec [`PartitionedCache::set_quota`].
+//! - Consulter les statistiques d'un espace de noms avec [`PartitionedCache::stats`].
+//! - Vider un espace de noms avec [`PartitionedCache::clear_namespace`].
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::cache_lru::Cache;
+use crate::stats::Stats;
+
+/// # Structure `Quota`
+///
+/// Parts de la capacité totale réservées à un espace de noms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    /// Nombre d'éléments garantis : la partition n'est jamais réduite en dessous.
+    pub min: usize,
+    /// Nombre maximal d'éléments de la partition.
+    pub max: usize,
+}
+
+// Partition d'un espace de noms.
+#[derive(Debug)]
+struct Partition<K, V> {
+    cache: Cache<K, V>,
+    quota: Quota,
+    stats: Stats,
+}
+
+/// # Structure `PartitionedCache`
+///
+/// Cette structure représente un cache LRU dont la capacité est partagée entre
+/// plusieurs espaces de noms. Par défaut, un espace de noms n'a pas de part
+/// garantie et peut occuper toute la capacité.
+///
+/// ## Types génériques
+/// - `N` : Le type des espaces de noms (doit être `Eq`, `Hash`, et `Clone`).
+/// - `K` : Le type des clés (doit être `Eq`, `Hash`, et `Clone`).
+/// - `V` : Le type des valeurs.
+#[derive(Debug)]
+pub struct PartitionedCache<N, K, V> {
+    capacity: usize,
+    len: usize,
+    partitions: HashMap<N, Partition<K, V>>,
+    // Défauts des espaces de noms qui n'ont pas encore de partition, repris par leur
+    // partition à sa création.
+    misses: HashMap<N, u64>,
+}
+
+impl<N: Eq + Hash + Clone, K: Eq + Hash + Clone, V> PartitionedCache<N, K, V> {
+    /// ## Méthode `new`
+    ///
+    /// Crée un cache partitionné avec une capacité totale donnée.
+    ///
+    /// ### Arguments
+    /// - `capacity` : La capacité totale, partagée entre les espaces de noms.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::partition::PartitionedCache;
+    /// let cache: PartitionedCache<&str, &str, String> = PartitionedCache::new(100);
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            len: 0,
+            partitions: HashMap::new(),
+            misses: HashMap::new(),
+        }
+    }
+
+    /// ## Méthode `set_quota`
+    ///
+    /// Définit la part minimale garantie et la part maximale d'un espace de noms.
+    /// Si la partition dépasse déjà sa part maximale, ses éléments les moins
+    /// récemment utilisés sont retirés. La somme des parts minimales ne devrait pas
+    /// dépasser la capacité totale.
+    ///
+    /// ### Arguments
+    /// - `namespace` : L'espace de noms.
+    /// - `min` : Le nombre d'éléments garantis.
+    /// - `max` : Le nombre maximal d'éléments (au moins égal à `min`).
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::partition::PartitionedCache;
+    /// let mut cache: PartitionedCache<&str, &str, String> = PartitionedCache::new(100);
+    /// cache.set_quota("client_a", 10, 50);
+    /// ```
+    pub fn set_quota(&mut self, namespace: N, min: usize, max: usize) {
+        let max = max.max(min);
+        self.partition(namespace.clone()).quota = Quota { min, max };
+        while self.namespace_len(&namespace) > max {
+            self.evict_from(&namespace);
+        }
+    }
+
+    /// ## Méthode `quota`
+    ///
+    /// Retourne les parts d'un espace de noms.
+    pub fn quota(&self, namespace: &N) -> Quota {
+        self.partitions
+            .get(namespace)
+            .map(|partition| partition.quota)
+            .unwrap_or(self.default_quota())
+    }
+
+    /// ## Méthode `put`
+    ///
+    /// Ajoute une clé et une valeur dans un espace de noms. Si la partition a atteint
+    /// sa part maximale, son plus ancien élément est retiré. Sinon, si la capacité
+    /// totale est atteinte, le plus ancien élément de la partition qui dépasse le
+    /// plus sa part équitable est retiré. Si aucun élément ne peut être retiré, par
+    /// exemple avec une part maximale nulle, l'élément n'est pas ajouté.
+    ///
+    /// ### Arguments
+    /// - `namespace` : L'espace de noms.
+    /// - `key` : La clé associée à la valeur.
+    /// - `value` : La valeur à stocker.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::partition::PartitionedCache;
+    /// let mut cache = PartitionedCache::new(100);
+    /// cache.put("client_a", "A", "Valeur A".to_string());
+    /// ```
+    pub fn put(&mut self, namespace: N, key: K, value: V) {
+        let is_new = !self
+            .partitions
+            .get(&namespace)
+            .is_some_and(|partition| partition.cache.contains(&key));
+
+        if is_new {
+            let at_max = {
+                let partition = self.partition(namespace.clone());
+                partition.cache.len() >= partition.quota.max
+            };
+            let has_room = if at_max {
+                self.evict_from(&namespace)
+            } else if self.len >= self.capacity {
+                let victim = self.victim(&namespace);
+                self.evict_from(&victim)
+            } else {
+                true
+            };
+            if !has_room {
+                return;
+            }
+            self.len += 1;
+        }
+
+        let partition = self.partition(namespace);
+        partition.cache.put(key, value);
+        partition.stats.insertions += 1;
+    }
+
+    /// ## Méthode `get`
+    ///
+    /// Récupère une valeur dans un espace de noms et la marque comme récemment utilisée.
+    /// Un défaut est compté dans les statistiques de l'espace de noms, même s'il n'a
+    /// encore aucun élément ; aucune partition n'est alors créée.
+    ///
+    /// ### Retourne
+    /// - `Some(&V)` : La valeur liée à la clé si elle est présente.
+    /// - `None` : Si la clé n'est pas présente dans cet espace de noms.
+    pub fn get(&mut self, namespace: &N, key: &K) -> Option<&V> {
+        let Some(partition) = self.partitions.get_mut(namespace) else {
+            *self.misses.entry(namespace.clone()).or_default() += 1;
+            return None;
+        };
+        match partition.cache.get(key) {
+            Some(value) => {
+                partition.stats.hits += 1;
+                Some(value)
+            }
+            None => {
+                partition.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// ## Méthode `remove`
+    ///
+    /// Retire une clé d'un espace de noms.
+    ///
+    /// ### Retourne
+    /// - `Some(V)` : La valeur retirée.
+    /// - `None` : Si la clé n'est pas présente dans cet espace de noms.
+    pub fn remove(&mut self, namespace: &N, key: &K) -> Option<V> {
+        let value = self.partitions.get_mut(namespace)?.cache.remove(key)?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// ## Méthode `clear_namespace`
+    ///
+    /// Retire tous les éléments d'un espace de noms. Ses parts et ses statistiques
+    /// sont conservées.
+    ///
+    /// ### Retourne
+    /// Le nombre d'éléments retirés.
+    pub fn clear_namespace(&mut self, namespace: &N) -> usize {
+        let Some(partition) = self.partitions.get_mut(namespace) else {
+            return 0;
+        };
+        let removed = partition.cache.len();
+        partition.cache = Cache::new(self.capacity);
+        self.len -= removed;
+        removed
+    }
+
+    /// ## Méthode `stats`
+    ///
+    /// Retourne les statistiques d'un espace de noms.
+    pub fn stats(&self, namespace: &N) -> Stats {
+        match self.partitions.get(namespace) {
+            Some(partition) => partition.stats,
+            None => Stats {
+                misses: self.misses.get(namespace).copied().unwrap_or(0),
+                ..Stats::default()
+            },
+        }
+    }
+
+    /// ## Méthode `namespace_len`
+    ///
+    /// Retourne le nombre d'éléments d'un espace de noms.
+    pub fn namespace_len(&self, namespace: &N) -> usize {
+        self.partitions
+            .get(namespace)
+            .map_or(0, |partition| partition.cache.len())
+    }
+
+    /// ## Méthode `len`
+    ///
+    /// Retourne le nombre total d'éléments, tous espaces de noms confondus.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// ## Méthode `is_empty`
+    ///
+    /// Indique si le cache est vide.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn default_quota(&self) -> Quota {
+        Quota {
+            min: 0,
+            max: self.capacity,
+        }
+    }
+
+    // Récupère la partition d'un espace de noms, en la créant si besoin.
+    fn partition(&mut self, namespace: N) -> &mut Partition<K, V> {
+        let capacity = self.capacity;
+        let quota = self.default_quota();
+        let misses = &mut self.misses;
+        self.partitions
+            .entry(namespace)
+            .or_insert_with_key(|namespace| Partition {
+                cache: Cache::new(capacity),
+                quota,
+                stats: Stats {
+                    misses: misses.remove(namespace).unwrap_or(0),
+                    ..Stats::default()
+                },
+            })
+    }
+
+    // Choisit la partition qui dépasse le plus sa part équitable, sans descendre
+    // sous sa part minimale. La part équitable est la capacité divisée par le nombre
+    // de partitions actives, bornée par les parts de chaque partition. Si toutes
+    // sont à leur part minimale, l'élément ajouté remplace le plus ancien de sa
+    // propre partition, qui garde sa taille ; il n'est pas ajouté si elle est vide.
+    fn victim(&self, inserting: &N) -> N {
+        let active = self
+            .partitions
+            .iter()
+            .filter(|(namespace, partition)| !partition.cache.is_empty() || *namespace == inserting)
+            .count()
+            .max(1);
+        let fair_share = self.capacity / active;
+
+        self.partitions
+            .iter()
+            .filter(|(_, partition)| partition.cache.len() > partition.quota.min)
+            .max_by_key(|(namespace, partition)| {
+                let fair = fair_share.clamp(partition.quota.min, partition.quota.max);
+                let over = partition.cache.len() as isize - fair as isize;
+                // À dépassement égal, la partition qui insère est choisie.
+                (over, *namespace == inserting)
+            })
+            .map(|(namespace, _)| namespace.clone())
+            .unwrap_or_else(|| inserting.clone())
+    }
+
+    // Retire l'élément le moins récemment utilisé d'une partition. Retourne `false`
+    // si la partition est vide.
+    fn evict_from(&mut self, namespace: &N) -> bool {
+        let Some(partition) = self.partitions.get_mut(namespace) else {
+            return false;
+        };
+        if partition.cache.pop_lru().is_none() {
+            return false;
+        }
+        partition.stats.evictions += 1;
+        self.len -= 1;
+        true
+    }
+}
@@ -0,0 +1,364 @@
+//! Module implémentant un cache LRU à chargement automatique avec expiration
+//! et rafraîchissement anticipé.
+//!
+//! Chaque élément est chargé grâce à une fonction de chargement et expire après
+//! une durée `ttl`. Passé la durée `refresh_after`, la lecture suivante retourne
+//! la valeur actuelle et déclenche son rechargement, en ligne ou dans un thread.
+//! Une période de grâce optionnelle permet de servir une valeur expirée pendant
+//! sa revalidation, y compris lorsque la revalidation échoue.
+//!
+//! ## Fonctionnalités principales
+//! - Récupérer une valeur, chargée si besoin, avec [`RefreshCache::get`].
+//! - Savoir si la valeur retournée est expirée avec [`RefreshCache::fetch`].
+//! - Choisir le mode de rechargement avec [`RefreshCache::set_mode`].
+//! - Définir la période de grâce avec [`RefreshCache::set_grace`].
+//! - Ajouter ou invalider des valeurs avec [`RefreshCache::put`] et [`RefreshCache::invalidate`].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cache_lru::Cache;
+
+/// Fonction de chargement d'une valeur à partir de sa clé.
+pub type Loader<K, V> = dyn Fn(&K) -> io::Result<V> + Send + Sync;
+
+/// # Enum `RefreshMode`
+///
+/// Manière dont un rechargement anticipé est effectué.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshMode {
+    /// Le rechargement est effectué par la lecture qui le déclenche.
+    Inline,
+    /// Le rechargement est effectué dans un thread dédié.
+    Background,
+}
+
+/// # Structure `Fetched`
+///
+/// Valeur retournée par [`RefreshCache::fetch`], accompagnée de son état.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fetched<V> {
+    /// La valeur associée à la clé.
+    pub value: V,
+    /// `true` si la valeur a expiré et est servie pendant sa période de grâce.
+    pub stale: bool,
+}
+
+impl<V> Fetched<V> {
+    fn fresh(value: V) -> Self {
+        Self {
+            value,
+            stale: false,
+        }
+    }
+}
+
+// Valeur stockée dans le cache avec sa date de chargement.
+#[derive(Debug, Clone)]
+struct Entry<V> {
+    value: V,
+    loaded_at: Instant,
+}
+
+// État partagé avec les threads de rechargement.
+struct Shared<K, V> {
+    cache: Mutex<Cache<K, Entry<V>>>,
+    // Valeurs en cours de rechargement, servies même si elles ont expiré ou été
+    // évincées, avec le numéro de leur rechargement. Un ajout ou une invalidation
+    // retire la clé : le rechargement en cours n'écrit alors plus son résultat.
+    refreshing: Mutex<HashMap<K, (u64, Entry<V>)>>,
+    next_refresh: AtomicU64,
+}
+
+/// # Structure `RefreshCache`
+///
+/// Cette structure représente un cache LRU dont les valeurs sont chargées par une
+/// fonction de chargement, expirent après `ttl` et sont rechargées par anticipation
+/// après `refresh_after`. Une clé en cours de rechargement n'est jamais servie comme
+/// un défaut de cache : passé `ttl`, elle est servie marquée comme expirée.
+///
+/// ## Types génériques
+/// - `K` : Le type des clés (doit être `Eq`, `Hash`, `Clone` et `Send`).
+/// - `V` : Le type des valeurs (doit être `Clone` et `Send`).
+pub struct RefreshCache<K, V> {
+    shared: Arc<Shared<K, V>>,
+    loader: Arc<Loader<K, V>>,
+    ttl: Duration,
+    refresh_after: Duration,
+    grace: Duration,
+    mode: RefreshMode,
+}
+
+impl<K, V> RefreshCache<K, V>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+    V: Clone + Send + 'static,
+{
+    /// ## Méthode `new`
+    ///
+    /// Crée un cache à chargement automatique. Le rechargement est effectué en ligne
+    /// par défaut.
+    ///
+    /// ### Arguments
+    /// - `capacity` : La capacité maximale du cache.
+    /// - `ttl` : La durée après laquelle une valeur expire.
+    /// - `refresh_after` : La durée après laquelle une valeur est rechargée par
+    ///   anticipation, ramenée à `ttl` si elle est plus longue.
+    /// - `loader` : La fonction de chargement des valeurs.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::refresh::RefreshCache;
+    /// # use std::time::Duration;
+    /// let cache = RefreshCache::new(
+    ///     3,
+    ///     Duration::from_secs(60),
+    ///     Duration::from_secs(45),
+    ///     |key: &u32| Ok(key * 2),
+    /// );
+    /// assert_eq!(cache.get(&21).unwrap(), 42);
+    /// ```
+    pub fn new<F>(capacity: usize, ttl: Duration, refresh_after: Duration, loader: F) -> Self
+    where
+        F: Fn(&K) -> io::Result<V> + Send + Sync + 'static,
+    {
+        Self {
+            shared: Arc::new(Shared {
+                cache: Mutex::new(Cache::new(capacity)),
+                refreshing: Mutex::new(HashMap::new()),
+                next_refresh: AtomicU64::new(0),
+            }),
+            loader: Arc::new(loader),
+            ttl,
+            refresh_after: refresh_after.min(ttl),
+            grace: Duration::ZERO,
+            mode: RefreshMode::Inline,
+        }
+    }
+
+    /// ## Méthode `set_mode`
+    ///
+    /// Définit la manière dont les rechargements anticipés sont effectués.
+    pub fn set_mode(&mut self, mode: RefreshMode) {
+        self.mode = mode;
+    }
+
+    /// ## Méthode `set_grace`
+    ///
+    /// Définit la période de grâce pendant laquelle une valeur expirée est encore
+    /// servie, marquée comme expirée, pendant sa revalidation. Si la revalidation
+    /// échoue, la valeur expirée reste servie jusqu'à la fin de cette période.
+    ///
+    /// ### Arguments
+    /// - `grace` : La durée de la période de grâce, comptée à partir de l'expiration.
+    pub fn set_grace(&mut self, grace: Duration) {
+        self.grace = grace;
+    }
+
+    /// ## Méthode `get`
+    ///
+    /// Récupère une valeur à partir de sa clé. La valeur est chargée si elle est
+    /// absente ou expirée. Si elle est plus ancienne que `refresh_after`, la valeur
+    /// actuelle est retournée et son rechargement est déclenché.
+    ///
+    /// ### Retourne
+    /// - `Ok(V)` : La valeur associée à la clé.
+    /// - `Err(io::Error)` : Si le chargement d'une valeur absente ou expirée échoue.
+    pub fn get(&self, key: &K) -> io::Result<V> {
+        self.fetch(key).map(|fetched| fetched.value)
+    }
+
+    /// ## Méthode `fetch`
+    ///
+    /// Identique à [`RefreshCache::get`], mais indique si la valeur retournée a
+    /// expiré et est servie pendant sa période de grâce.
+    ///
+    /// ### Retourne
+    /// - `Ok(Fetched<V>)` : La valeur associée à la clé et son état.
+    /// - `Err(io::Error)` : Si le chargement d'une valeur absente, ou expirée
+    ///   depuis plus longtemps que la période de grâce, échoue.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::refresh::RefreshCache;
+    /// # use std::time::Duration;
+    /// let cache = RefreshCache::new(3, Duration::from_secs(60), Duration::from_secs(45), |key: &u32| {
+    ///     Ok(key * 2)
+    /// });
+    /// let fetched = cache.fetch(&21).unwrap();
+    /// assert_eq!(fetched.value, 42);
+    /// assert!(!fetched.stale);
+    /// ```
+    pub fn fetch(&self, key: &K) -> io::Result<Fetched<V>> {
+        // Une clé en cours de rechargement est servie jusqu'à la fin du rechargement,
+        // marquée comme expirée au-delà de `ttl`, sans lancer un second chargement.
+        if let Some((_, entry)) = self.shared.refreshing.lock().unwrap().get(key).cloned() {
+            return Ok(Fetched {
+                stale: entry.loaded_at.elapsed() >= self.ttl,
+                value: entry.value,
+            });
+        }
+
+        let cached = self.shared.cache.lock().unwrap().get(key).cloned();
+        if let Some(entry) = cached {
+            let age = entry.loaded_at.elapsed();
+            if age < self.refresh_after {
+                return Ok(Fetched::fresh(entry.value));
+            }
+            if age < self.ttl {
+                self.refresh(key, entry.clone());
+                return Ok(Fetched::fresh(entry.value));
+            }
+            if age < self.ttl + self.grace {
+                return Ok(match self.refresh(key, entry.clone()) {
+                    Some(value) => Fetched::fresh(value),
+                    None => Fetched {
+                        value: entry.value,
+                        stale: true,
+                    },
+                });
+            }
+        }
+
+        let value = (self.loader)(key)?;
+        self.put(key.clone(), value.clone());
+        Ok(Fetched::fresh(value))
+    }
+
+    /// ## Méthode `put`
+    ///
+    /// Ajoute ou remplace une valeur, considérée comme chargée maintenant. Un
+    /// rechargement en cours de cette clé ne la remplacera pas.
+    pub fn put(&self, key: K, value: V) {
+        let entry = Entry {
+            value,
+            loaded_at: Instant::now(),
+        };
+        let mut cache = self.shared.cache.lock().unwrap();
+        self.shared.refreshing.lock().unwrap().remove(&key);
+        cache.put(key, entry);
+    }
+
+    /// ## Méthode `invalidate`
+    ///
+    /// Retire une valeur du cache ; elle sera chargée à la prochaine lecture. Un
+    /// rechargement en cours de cette clé ne la remettra pas en cache.
+    pub fn invalidate(&self, key: &K) {
+        let mut cache = self.shared.cache.lock().unwrap();
+        self.shared.refreshing.lock().unwrap().remove(key);
+        cache.remove(key);
+    }
+
+    /// ## Méthode `is_refreshing`
+    ///
+    /// Indique si une clé est en cours de rechargement.
+    pub fn is_refreshing(&self, key: &K) -> bool {
+        self.shared.refreshing.lock().unwrap().contains_key(key)
+    }
+
+    // Recharge une clé selon le mode choisi, sauf si elle est déjà en cours de rechargement.
+    // Retourne la nouvelle valeur si elle a été rechargée en ligne avec succès.
+    fn refresh(&self, key: &K, current: Entry<V>) -> Option<V> {
+        let id = self.shared.next_refresh.fetch_add(1, Ordering::Relaxed);
+        {
+            let mut refreshing = self.shared.refreshing.lock().unwrap();
+            if refreshing.contains_key(key) {
+                return None;
+            }
+            refreshing.insert(key.clone(), (id, current));
+        }
+
+        let shared = Arc::clone(&self.shared);
+        let loader = Arc::clone(&self.loader);
+        let key = key.clone();
+        match self.mode {
+            RefreshMode::Inline => reload(&shared, &*loader, key, id),
+            RefreshMode::Background => {
+                thread::spawn(move || reload(&shared, &*loader, key, id));
+                None
+            }
+        }
+    }
+}
+
+// Recharge une clé ; en cas d'échec, l'ancienne valeur est conservée jusqu'à la fin
+// de sa période de grâce. Rien n'est écrit si la clé a été ajoutée ou invalidée
+// pendant le rechargement `id`.
+fn reload<K: Eq + Hash + Clone, V: Clone>(
+    shared: &Shared<K, V>,
+    loader: &Loader<K, V>,
+    key: K,
+    id: u64,
+) -> Option<V> {
+    let guard = RefreshGuard {
+        shared,
+        key: &key,
+        id,
+    };
+    let reloaded = loader(&key).ok();
+    drop(guard);
+    let mut cache = shared.cache.lock().unwrap();
+    let mut refreshing = shared.refreshing.lock().unwrap();
+    if refreshing
+        .get(&key)
+        .is_none_or(|(current, _)| *current != id)
+    {
+        return reloaded;
+    }
+    match (&reloaded, refreshing.remove(&key)) {
+        (Some(value), _) => {
+            let entry = Entry {
+                value: value.clone(),
+                loaded_at: Instant::now(),
+            };
+            cache.put(key, entry);
+        }
+        // La valeur a pu être évincée pendant le rechargement : on la remet en cache.
+        (None, Some((_, previous))) if !cache.contains(&key) => {
+            cache.put(key, previous);
+        }
+        (None, _) => {}
+    }
+    reloaded
+}
+
+// Retire la clé des rechargements en cours si la fonction de chargement panique,
+// pour qu'elle soit de nouveau chargée à la lecture suivante.
+struct RefreshGuard<'a, K: Eq + Hash, V> {
+    shared: &'a Shared<K, V>,
+    key: &'a K,
+    id: u64,
+}
+
+impl<K: Eq + Hash, V> Drop for RefreshGuard<'_, K, V> {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            return;
+        }
+        if let Ok(mut refreshing) = self.shared.refreshing.lock() {
+            if refreshing
+                .get(self.key)
+                .is_some_and(|(current, _)| *current == self.id)
+            {
+                refreshing.remove(self.key);
+            }
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for RefreshCache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RefreshCache")
+            .field("ttl", &self.ttl)
+            .field("refresh_after", &self.refresh_after)
+            .field("grace", &self.grace)
+            .field("mode", &self.mode)
+            .finish_non_exhaustive()
+    }
+}
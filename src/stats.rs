@@ -0,0 +1,37 @@
+//! Module regroupant les statistiques d'utilisation d'un cache.
+
+/// # Structure `Stats`
+///
+/// Compteurs cumulés des accès, ajouts et évictions d'un cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Nombre de lectures ayant trouvé la clé.
+    pub hits: u64,
+    /// Nombre de lectures n'ayant pas trouvé la clé.
+    pub misses: u64,
+    /// Nombre d'éléments ajoutés ou mis à jour.
+    pub insertions: u64,
+    /// Nombre d'éléments retirés pour libérer de la place.
+    pub evictions: u64,
+}
+
+impl Stats {
+    /// ## Méthode `hit_ratio`
+    ///
+    /// Retourne la proportion de lectures ayant trouvé la clé, entre `0.0` et `1.0`.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::stats::Stats;
+    /// let stats = Stats { hits: 3, misses: 1, ..Stats::default() };
+    /// assert_eq!(stats.hit_ratio(), 0.75);
+    /// ```
+    pub fn hit_ratio(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
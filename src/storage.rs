@@ -1,44 +1,229 @@
 //! Module pour la gestion d'un cache LRU persistant.
 //!
+//! Chaque modification est ajoutée à la fin d'un journal (par défaut, le fichier de
+//! sauvegarde suivi de `.wal`), ce qui rend son coût d'écriture constant. Au-delà
+//! d'un seuil d'opérations, le journal est compacté : le contenu du cache est
+//! réécrit dans la sauvegarde et le journal est vidé. À l'ouverture, la sauvegarde
+//! est chargée puis le journal est rejoué.
+//!
 //! ## Fonctionnalités principales
-//! - Ajouter des éléments au cache et les sauvegarder automatiquement.
+//! - Ajouter des éléments au cache et les enregistrer automatiquement dans le journal,
+//!   immédiatement ou en différé ([`PersistentCache::set_write_mode`]), dans le
+//!   thread appelant ou dans un thread dédié ([`PersistentCache::spawn_writer`]).
 //! - Récupérer des éléments depuis le cache en mettant à jour leur priorité.
 //! - Sauvegarder l'état actuel du cache dans un fichier.
-//! - Charger les données d'un fichier pour restaurer un cache persistant.
+//! - Charger les données d'un fichier pour restaurer un cache persistant, en
+//!   refusant le fichier ou en ignorant ses lignes invalides ([`LoadOptions`]).
+//! - Partager un même fichier entre plusieurs processus, avec des verrous et un
+//!   rechargement avant écriture ([`PersistentCache::set_sharing`]).
+//! - Conserver les dernières sauvegardes et restaurer le cache depuis l'une d'elles
+//!   ([`PersistentCache::set_retention`]).
+//! - Enregistrer avec chaque élément sa date d'insertion, son expiration, son nombre
+//!   d'accès, son poids et ses tags ([`EntryMeta`]), et avec le cache ses
+//!   statistiques ([`PersistentCache::put_with_meta`], [`PersistentCache::stats`]).
+//! - Exporter et importer les éléments aux formats JSON Lines et CSV
+//!   ([`PersistentCache::export`], [`PersistentCache::import`]).
+//!
+//! Les clés et les valeurs sont converties en octets par un [`Codec`] : par défaut,
+//! leur représentation textuelle ([`StringCodec`]). La sauvegarde et le journal sont
+//! conservés par un [`Store`] : par défaut, un fichier unique ([`FileStore`]).
+//!
+//! Le format des fichiers de sauvegarde et du journal est décrit dans le module [`format`].
+//! Les fichiers des versions précédentes sont migrés au chargement par le module
+//! [`migration`], et peuvent être réécrits dans la version actuelle
+//! ([`PersistentCache::upgrade_in_place`]).
+//! Les fichiers de sauvegarde peuvent aussi être écrits au format binaire décrit dans
+//! le module [`binary`] ([`SnapshotFormat`]), détecté automatiquement au chargement.
+//! Elles peuvent être compressées ou chiffrées, ainsi que le journal, par les
+//! transformations du module [`transform`] ([`PersistentCache::add_transform`]).
+//! Les opérations de persistance ne paniquent pas : elles retournent une [`StorageError`].
+
+pub mod binary;
+pub mod codec;
+pub mod error;
+pub mod format;
+pub mod meta;
+pub mod migration;
+pub mod store;
+pub mod transform;
+mod writer;
+
+pub use codec::{BytesCodec, Codec, IntCodec, StringCodec};
+pub use error::{BadLine, StorageError};
+pub use meta::{CacheMeta, EntryMeta};
+pub use store::{ArchiveNaming, DirStore, FileStore, MemoryStore, Store};
+pub use transform::{Lz, Transform};
 
 use std::collections::HashMap;
-use std::fs::{self, OpenOptions};
-use std::io::{self, Write};
+use std::fmt::{Debug, Display};
+use std::io::{self, Read, Write};
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::backend::WriteMode;
+use crate::cache_lru::Cache;
+use crate::exchange::{self, ExchangeFormat, ImportReport, Overflow};
+use crate::stats::Stats;
+use format::LogRecord;
+use migration::Migrations;
+use writer::{SharedStore, Sink};
+
+/// # Structure `LoadOptions`
+///
+/// Options de [`PersistentCache::load_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// Si `true`, les lignes invalides sont ignorées et listées dans le
+    /// [`LoadReport`] ; sinon, la première ligne invalide fait échouer le chargement.
+    pub skip_bad_lines: bool,
+    /// Traitement du contenu actuel du cache.
+    pub mode: LoadMode,
+}
+
+impl LoadOptions {
+    /// Le chargement échoue à la première ligne invalide.
+    pub fn strict() -> Self {
+        Self {
+            skip_bad_lines: false,
+            mode: LoadMode::Replace,
+        }
+    }
+
+    /// Les lignes invalides sont ignorées.
+    pub fn skip_bad_lines() -> Self {
+        Self {
+            skip_bad_lines: true,
+            mode: LoadMode::Replace,
+        }
+    }
+
+    /// Change le traitement du contenu actuel du cache.
+    pub fn with_mode(self, mode: LoadMode) -> Self {
+        Self { mode, ..self }
+    }
+}
+
+/// # Enum `LoadMode`
+///
+/// Traitement du contenu actuel du cache lors d'un chargement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoadMode {
+    /// Le contenu actuel est remplacé par celui des fichiers.
+    #[default]
+    Replace,
+    /// Le contenu des fichiers est ajouté au contenu actuel, comme s'il venait
+    /// d'être inséré : il devient le plus récent et remplace les valeurs des clés
+    /// déjà présentes.
+    Merge,
+}
+
+/// # Enum `Sharing`
+///
+/// Partage du stockage d'un [`PersistentCache`] entre plusieurs processus.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Sharing {
+    /// Le stockage n'est utilisé que par ce cache : aucun verrou n'est pris.
+    #[default]
+    Private,
+    /// Le stockage est verrouillé en lecture pendant chaque chargement, et en
+    /// écriture pendant chaque opération qui l'écrit.
+    Locked,
+    /// Comme [`Sharing::Locked`]. De plus, avant chaque écriture, si le stockage a
+    /// été modifié par un autre processus depuis le dernier chargement ou la dernière
+    /// écriture de ce cache, les opérations en attente y sont écrites, puis il est
+    /// rechargé avec le mode indiqué.
+    ReloadOnChange(LoadMode),
+}
+
+/// # Structure `LoadReport`
+///
+/// Résultat d'un chargement réussi.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// Nombre d'éléments chargés.
+    pub loaded: usize,
+    /// Lignes ignorées car invalides.
+    pub skipped: Vec<BadLine>,
+    /// Nombre d'octets ignorés à la fin d'un fichier binaire, à partir du premier
+    /// élément tronqué ou corrompu.
+    pub discarded_bytes: usize,
+    /// Nombre d'éléments ignorés car expirés.
+    pub expired: usize,
+    /// Métadonnées du cache enregistrées avec la sauvegarde, absentes pour les
+    /// fichiers des versions précédentes du format. En mode [`LoadMode::Replace`],
+    /// la capacité enregistrée remplace celle donnée à la création du cache.
+    pub cache_meta: Option<CacheMeta>,
+}
+
+/// # Enum `SnapshotFormat`
+///
+/// Format des fichiers de sauvegarde écrits par [`PersistentCache::save`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    /// Format texte, décrit dans le module [`format`].
+    #[default]
+    Text,
+    /// Format binaire avec somme de contrôle, décrit dans le module [`binary`].
+    Binary,
+}
 
 /// # Structure `PersistentCache`
 ///
-/// Cette structure représente un cache LRU persistant. Elle sauvegarde
-/// automatiquement les données dans un fichier chaque fois qu'un élément
-/// est ajouté ou modifié.
+/// Cette structure représente un cache LRU persistant. Chaque élément ajouté,
+/// modifié ou retiré est enregistré automatiquement dans un journal, compacté
+/// régulièrement dans la sauvegarde.
 ///
 /// ## Types génériques
-/// - `K` : Type des clés (doit être `Eq`, `Hash`, `Clone`, `ToString`, et `FromStr`).
-/// - `V` : Type des valeurs (doit être `ToString` et `FromStr`).
+/// - `K` : Type des clés (doit être `Eq`, `Hash`, et `Clone`).
+/// - `V` : Type des valeurs.
+/// - `KC` : Conversion des clés en octets ([`StringCodec`] par défaut).
+/// - `VC` : Conversion des valeurs en octets ([`StringCodec`] par défaut).
 #[derive(Debug)]
-pub struct PersistentCache<K, V> {
-    capacity: usize,
-    map: HashMap<K, V>,
-    keys: Vec<K>,
-    file_path: String,
+pub struct PersistentCache<K, V, KC = StringCodec, VC = StringCodec> {
+    cache: Cache<K, Entry<V>>,
+    stats: Stats,
+    key_codec: KC,
+    value_codec: VC,
+    store: SharedStore,
+    sink: Sink,
+    // Parties de la sauvegarde modifiées depuis leur dernière écriture.
+    dirty_shards: Vec<bool>,
+    log_records: usize,
+    compaction_threshold: usize,
+    format: SnapshotFormat,
+    // Transformations appliquées aux sauvegardes, dans l'ordre.
+    transforms: Vec<Box<dyn Transform>>,
+    // Migrations des anciennes versions des sauvegardes texte et du journal.
+    snapshot_migrations: Migrations,
+    log_migrations: Migrations,
+    write_mode: WriteMode,
+    // Opérations en attente d'écriture, au plus une par clé encodée.
+    pending: Vec<Option<LogRecord>>,
+    pending_keys: HashMap<Vec<u8>, usize>,
+    flush_threshold: usize,
+    flush_interval: Option<Duration>,
+    track_reads: bool,
+    sharing: Sharing,
+    // Génération du stockage après le dernier chargement ou la dernière écriture.
+    seen_generation: u64,
+    // Nombre d'archives conservées (aucune si 0) et leur nom.
+    retention: usize,
+    archive_naming: ArchiveNaming,
+    last_flush: Instant,
 }
 
 impl<K, V> PersistentCache<K, V>
 where
-    K: Eq + std::hash::Hash + Clone + ToString + FromStr,
-    V: ToString + FromStr,
-    <K as FromStr>::Err: std::fmt::Debug,
-    <V as FromStr>::Err: std::fmt::Debug,
+    K: Eq + std::hash::Hash + Clone,
+    StringCodec: Codec<K> + Codec<V>,
 {
     /// ## Méthode `new`
     ///
-    /// Crée un cache avec une capacité maximale et un chemin vers un fichier
-    /// de sauvegarde.
+    /// Crée un cache vide avec une capacité maximale et un chemin vers un fichier
+    /// de sauvegarde. Les clés et les valeurs sont enregistrées sous leur forme
+    /// textuelle. Le journal est compacté après `2 * capacity` opérations.
     ///
     /// ### Arguments
     /// - `capacity` : Capacité maximale du cache.
@@ -46,99 +231,1087 @@ where
     ///
     /// ### Exemple
     /// ```rust
+    /// # use cache_lru::storage::PersistentCache;
     /// let cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
     /// ```
-    pub fn new(capacity: usize, file_path: &str) -> Self {
+    pub fn new(capacity: usize, file_path: impl AsRef<Path>) -> Self {
+        Self::with_codecs(capacity, file_path, StringCodec, StringCodec)
+    }
+
+    /// ## Méthode `open`
+    ///
+    /// Crée un cache et le restaure depuis son fichier de sauvegarde et son journal,
+    /// s'ils existent.
+    ///
+    /// ### Arguments
+    /// - `capacity` : Capacité maximale du cache.
+    /// - `file_path` : Chemin du fichier de sauvegarde.
+    ///
+    /// ### Retourne
+    /// - `Ok(PersistentCache)` : Le cache restauré, vide si aucun fichier n'existe.
+    /// - `Err(StorageError)` : Si un fichier existe mais ne peut pas être chargé.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::storage::PersistentCache;
+    /// let cache: PersistentCache<String, String> = PersistentCache::open(3, "cache.txt").unwrap();
+    /// ```
+    pub fn open(capacity: usize, file_path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::open_with_codecs(capacity, file_path, StringCodec, StringCodec)
+    }
+}
+
+impl<K, V, KC, VC> PersistentCache<K, V, KC, VC>
+where
+    K: Eq + std::hash::Hash + Clone,
+    KC: Codec<K>,
+    VC: Codec<V>,
+{
+    /// ## Méthode `with_codecs`
+    ///
+    /// Identique à [`PersistentCache::new`], avec la conversion en octets des clés
+    /// et des valeurs.
+    ///
+    /// ### Arguments
+    /// - `capacity` : Capacité maximale du cache.
+    /// - `file_path` : Chemin du fichier de sauvegarde.
+    /// - `key_codec` : Conversion des clés.
+    /// - `value_codec` : Conversion des valeurs.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::storage::{BytesCodec, IntCodec, PersistentCache};
+    /// let cache: PersistentCache<u64, Vec<u8>, _, _> =
+    ///     PersistentCache::with_codecs(3, "cache.bin", IntCodec, BytesCodec);
+    /// ```
+    pub fn with_codecs(
+        capacity: usize,
+        file_path: impl AsRef<Path>,
+        key_codec: KC,
+        value_codec: VC,
+    ) -> Self {
+        Self::with_store(capacity, FileStore::new(file_path), key_codec, value_codec)
+    }
+
+    /// ## Méthode `open_with_codecs`
+    ///
+    /// Identique à [`PersistentCache::open`], avec la conversion en octets des clés
+    /// et des valeurs.
+    pub fn open_with_codecs(
+        capacity: usize,
+        file_path: impl AsRef<Path>,
+        key_codec: KC,
+        value_codec: VC,
+    ) -> Result<Self, StorageError> {
+        Self::open_with_store(capacity, FileStore::new(file_path), key_codec, value_codec)
+    }
+
+    /// ## Méthode `with_store`
+    ///
+    /// Identique à [`PersistentCache::with_codecs`], avec un stockage choisi au lieu
+    /// d'un fichier unique.
+    ///
+    /// ### Arguments
+    /// - `capacity` : Capacité maximale du cache.
+    /// - `store` : Le stockage de la sauvegarde et du journal.
+    /// - `key_codec` : Conversion des clés.
+    /// - `value_codec` : Conversion des valeurs.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::storage::{DirStore, PersistentCache, StringCodec};
+    /// let cache: PersistentCache<String, String> =
+    ///     PersistentCache::with_store(1000, DirStore::new("cache", 16), StringCodec, StringCodec);
+    /// ```
+    pub fn with_store(
+        capacity: usize,
+        store: impl Store + 'static,
+        key_codec: KC,
+        value_codec: VC,
+    ) -> Self {
+        let shards = store.shards().max(1);
+        let store: Box<dyn Store> = Box::new(store);
         Self {
-            capacity,
-            map: HashMap::new(),
-            keys: Vec::new(),
-            file_path: file_path.to_string(),
+            cache: Cache::new(capacity),
+            stats: Stats::default(),
+            key_codec,
+            value_codec,
+            store: Arc::new(Mutex::new(store)),
+            sink: Sink::Inline,
+            dirty_shards: vec![false; shards],
+            log_records: 0,
+            compaction_threshold: (2 * capacity).max(1),
+            format: SnapshotFormat::Text,
+            transforms: Vec::new(),
+            snapshot_migrations: Migrations::snapshot(),
+            log_migrations: Migrations::log(),
+            write_mode: WriteMode::WriteThrough,
+            pending: Vec::new(),
+            pending_keys: HashMap::new(),
+            flush_threshold: capacity.max(1),
+            flush_interval: None,
+            track_reads: false,
+            sharing: Sharing::Private,
+            seen_generation: 0,
+            retention: 0,
+            archive_naming: ArchiveNaming::Numbered,
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// ## Méthode `open_with_store`
+    ///
+    /// Identique à [`PersistentCache::open_with_codecs`], avec un stockage choisi au
+    /// lieu d'un fichier unique.
+    pub fn open_with_store(
+        capacity: usize,
+        store: impl Store + 'static,
+        key_codec: KC,
+        value_codec: VC,
+    ) -> Result<Self, StorageError> {
+        let mut cache = Self::with_store(capacity, store, key_codec, value_codec);
+        // Les restes d'une sauvegarde interrompue sont supprimés : la sauvegarde
+        // n'a pas été remplacée.
+        writer::lock(&cache.store).recover()?;
+        match cache.load() {
+            Ok(_) => Ok(cache),
+            Err(StorageError::Io(err)) if err.kind() == io::ErrorKind::NotFound => Ok(cache),
+            Err(err) => Err(err),
         }
     }
 
+    /// ## Méthode `set_compaction_threshold`
+    ///
+    /// Définit le nombre d'opérations du journal au-delà duquel il est compacté
+    /// dans le fichier de sauvegarde.
+    ///
+    /// ### Arguments
+    /// - `threshold` : Le nombre d'opérations (au moins 1).
+    pub fn set_compaction_threshold(&mut self, threshold: usize) {
+        self.compaction_threshold = threshold.max(1);
+    }
+
+    /// ## Méthode `set_format`
+    ///
+    /// Choisit le format des prochaines sauvegardes. Le chargement accepte les deux
+    /// formats, quel que soit ce choix.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::storage::{PersistentCache, SnapshotFormat};
+    /// let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.bin");
+    /// cache.set_format(SnapshotFormat::Binary);
+    /// ```
+    pub fn set_format(&mut self, format: SnapshotFormat) {
+        self.format = format;
+    }
+
+    /// ## Méthode `add_transform`
+    ///
+    /// Ajoute une transformation à la fin de celles appliquées aux prochaines
+    /// sauvegardes et aux prochaines écritures du journal, par exemple une
+    /// compression suivie d'un chiffrement. Les
+    /// transformations appliquées sont enregistrées dans la sauvegarde ; au
+    /// chargement, elles sont retrouvées par leur nom parmi celles ajoutées, quel
+    /// que soit leur ordre.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::storage::{Lz, PersistentCache};
+    /// let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.lz");
+    /// cache.add_transform(Lz);
+    /// ```
+    pub fn add_transform(&mut self, transform: impl Transform + 'static) {
+        self.transforms.push(Box::new(transform));
+    }
+
+    /// ## Méthode `set_migrations`
+    ///
+    /// Remplace les registres de migration utilisés au chargement des sauvegardes
+    /// au format texte et du journal, par défaut [`Migrations::snapshot`] et
+    /// [`Migrations::log`]. Permet de relire un ancien format propre à une
+    /// application ; les registres doivent viser les versions actuelles.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::storage::format::encode_record;
+    /// # use cache_lru::storage::migration::Migrations;
+    /// # use cache_lru::storage::PersistentCache;
+    /// let mut migrations = Migrations::snapshot();
+    /// // Les anciennes sauvegardes de l'application séparaient clé et valeur par `=`.
+    /// migrations.register(1, |line| {
+    ///     let (key, value) = line.split_once('=')?;
+    ///     Some(encode_record(key.as_bytes(), value.as_bytes()))
+    /// });
+    /// let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// cache.set_migrations(migrations, Migrations::log());
+    /// ```
+    pub fn set_migrations(&mut self, snapshot: Migrations, log: Migrations) {
+        self.snapshot_migrations = snapshot;
+        self.log_migrations = log;
+    }
+
+    /// ## Méthode `set_write_mode`
+    ///
+    /// Choisit quand les opérations sont écrites dans le journal :
+    /// - [`WriteMode::WriteThrough`] (par défaut) : à chaque opération.
+    /// - [`WriteMode::WriteBack`] : les clés modifiées sont marquées, et plusieurs
+    ///   opérations sur une même clé n'en forment plus qu'une. Elles sont écrites
+    ///   ensemble par [`PersistentCache::flush`], lorsque le nombre de clés
+    ///   modifiées atteint [`PersistentCache::set_flush_threshold`], lorsque le délai
+    ///   de [`PersistentCache::set_flush_interval`] est écoulé, ou à la destruction
+    ///   du cache. Une opération non écrite est perdue en cas d'arrêt brutal.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::backend::WriteMode;
+    /// # use cache_lru::storage::PersistentCache;
+    /// let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// cache.set_write_mode(WriteMode::WriteBack);
+    /// ```
+    pub fn set_write_mode(&mut self, mode: WriteMode) {
+        self.write_mode = mode;
+    }
+
+    /// ## Méthode `set_flush_threshold`
+    ///
+    /// Définit le nombre de clés modifiées à partir duquel elles sont écrites, en
+    /// mode [`WriteMode::WriteBack`]. Par défaut, la capacité du cache.
+    pub fn set_flush_threshold(&mut self, threshold: usize) {
+        self.flush_threshold = threshold.max(1);
+    }
+
+    /// ## Méthode `set_flush_interval`
+    ///
+    /// Définit le délai après lequel les clés modifiées sont écrites, en mode
+    /// [`WriteMode::WriteBack`]. Le délai est vérifié à chaque modification du cache.
+    /// Par défaut, aucun délai.
+    pub fn set_flush_interval(&mut self, interval: Option<Duration>) {
+        self.flush_interval = interval;
+    }
+
+    /// ## Méthode `set_track_reads`
+    ///
+    /// Si `true`, en mode [`WriteMode::WriteBack`], [`PersistentCache::get`] marque
+    /// aussi l'ordre d'utilisation comme modifié, pour que l'ordre enregistré reste
+    /// proche de l'ordre réel. Ce changement est écrit avec la modification suivante.
+    /// Dans tous les modes, la lecture d'un élément marque alors sa partie de la
+    /// sauvegarde comme modifiée, pour que son nombre d'accès soit enregistré à la
+    /// prochaine sauvegarde.
+    pub fn set_track_reads(&mut self, track_reads: bool) {
+        self.track_reads = track_reads;
+    }
+
+    /// ## Méthode `set_sharing`
+    ///
+    /// Choisit comment le stockage est partagé avec d'autres processus (voir
+    /// [`Sharing`]). Les verrous sont pris par le [`Store`], par exemple sur un
+    /// fichier `.lock` avec un [`FileStore`]. Avec [`PersistentCache::spawn_writer`],
+    /// chaque écriture attend la fin de celles du thread dédié avant de libérer le
+    /// verrou.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::storage::{LoadMode, PersistentCache, Sharing};
+    /// let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// cache.set_sharing(Sharing::ReloadOnChange(LoadMode::Replace));
+    /// ```
+    pub fn set_sharing(&mut self, sharing: Sharing) {
+        self.sharing = sharing;
+    }
+
+    /// ## Méthode `set_retention`
+    ///
+    /// Après chaque [`PersistentCache::save`], copie la sauvegarde dans une archive
+    /// et ne conserve que les `keep` plus récentes. Avec un [`FileStore`] sur
+    /// `cache.txt`, les archives sont `cache.txt.1`, `cache.txt.2`, ... ou
+    /// `cache.txt.@1760781300123`, ... selon `naming`. Les compactions automatiques
+    /// du journal ne créent pas d'archive.
+    ///
+    /// ### Arguments
+    /// - `keep` : Le nombre d'archives conservées, ou 0 pour ne pas archiver (par défaut).
+    /// - `naming` : Le nom des archives.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::storage::{ArchiveNaming, PersistentCache};
+    /// let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// cache.set_retention(5, ArchiveNaming::Numbered);
+    /// ```
+    pub fn set_retention(&mut self, keep: usize, naming: ArchiveNaming) {
+        self.retention = keep;
+        self.archive_naming = naming;
+    }
+
+    /// ## Méthode `archives`
+    ///
+    /// Liste les archives de la sauvegarde, de la plus récente à la plus ancienne
+    /// (voir [`Store::archives`]).
+    pub fn archives(&self) -> Result<Vec<String>, StorageError> {
+        Ok(writer::lock(&self.store).archives()?)
+    }
+
+    /// ## Méthode `restore`
+    ///
+    /// Remplace la sauvegarde par une archive, vide le journal, puis recharge le
+    /// cache. Les opérations en attente sont abandonnées.
+    ///
+    /// ### Arguments
+    /// - `name` : Le nom de l'archive, tel que retourné par [`PersistentCache::archives`].
+    ///
+    /// ### Retourne
+    /// - `Ok(LoadReport)` : Le résultat du chargement de l'archive.
+    /// - `Err(StorageError)` : Si l'archive n'existe pas ou ne peut pas être chargée.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::storage::PersistentCache;
+    /// # let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// cache.restore("2").unwrap();
+    /// ```
+    pub fn restore(&mut self, name: &str) -> Result<LoadReport, StorageError> {
+        self.shared_write(|cache| {
+            cache.pending.clear();
+            cache.pending_keys.clear();
+            cache.sink.sync()?;
+            writer::lock(&cache.store).restore(name)?;
+            cache.read_store(LoadOptions::strict())
+        })
+    }
+
+    /// ## Méthode `dirty_len`
+    ///
+    /// Retourne le nombre de clés dont les modifications ne sont pas encore écrites.
+    pub fn dirty_len(&self) -> usize {
+        self.pending_keys.len()
+    }
+
+    /// ## Méthode `flush`
+    ///
+    /// Écrit dans le journal, en une seule fois, toutes les opérations en attente.
+    ///
+    /// ### Retourne
+    /// - `Ok(())` : Si l'écriture réussit.
+    /// - `Err(StorageError)` : Si l'écriture échoue ; les opérations restent en attente.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::storage::PersistentCache;
+    /// # let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// cache.flush().unwrap();
+    /// ```
+    pub fn flush(&mut self) -> Result<(), StorageError> {
+        self.shared_write(|cache| {
+            cache.write_pending(None)?;
+            cache.compact_if_needed()
+        })
+    }
+
+    /// ## Méthode `spawn_writer`
+    ///
+    /// Confie toutes les écritures sur disque à un thread dédié. Les opérations
+    /// sont envoyées au thread par une file de taille bornée et retournent
+    /// immédiatement, sauf si la file est pleine : elles attendent alors qu'une
+    /// place se libère. Le thread regroupe les opérations reçues ensemble en une
+    /// seule écriture.
+    ///
+    /// Les erreurs d'écriture du thread sont retournées par [`PersistentCache::sync`].
+    /// À la destruction du cache, le thread termine les écritures en attente.
+    ///
+    /// ### Arguments
+    /// - `queue_capacity` : Nombre maximal d'envois en attente dans la file.
+    ///
+    /// ### Retourne
+    /// - `Ok(())` : Si le thread a démarré.
+    /// - `Err(StorageError::Io)` : Si le thread n'a pas pu être créé.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::storage::PersistentCache;
+    /// let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// cache.spawn_writer(1024).unwrap();
+    /// cache.put("A".to_string(), "Valeur A".to_string()).unwrap();
+    /// cache.sync().unwrap();
+    /// ```
+    pub fn spawn_writer(&mut self, queue_capacity: usize) -> Result<(), StorageError> {
+        self.sink.spawn(&self.store, queue_capacity)?;
+        Ok(())
+    }
+
+    /// ## Méthode `sync`
+    ///
+    /// Écrit les opérations en attente (voir [`PersistentCache::flush`]), puis attend
+    /// que toutes les écritures confiées au thread dédié soient sur disque.
+    ///
+    /// ### Retourne
+    /// - `Ok(())` : Si toutes les écritures ont réussi.
+    /// - `Err(StorageError::Io)` : La première erreur d'écriture depuis la
+    ///   synchronisation précédente.
+    pub fn sync(&mut self) -> Result<(), StorageError> {
+        self.flush()?;
+        self.sink.sync()?;
+        Ok(())
+    }
+
+    /// ## Méthode `log_len`
+    ///
+    /// Retourne le nombre d'opérations présentes dans le journal.
+    pub fn log_len(&self) -> usize {
+        self.log_records
+    }
+
     /// ## Méthode `save`
     ///
-    /// Sauvegarde les données du cache dans le fichier défini lors de la création.
+    /// Sauvegarde toutes les données du cache dans le stockage défini lors de la
+    /// création, au format décrit dans le module [`format`], puis vide le journal.
+    ///
+    /// Avec un [`FileStore`], les données sont d'abord écrites dans un fichier
+    /// temporaire du même dossier (le fichier de sauvegarde suivi de `.tmp`), qui
+    /// remplace ensuite le fichier de sauvegarde. En cas d'arrêt brutal, le fichier
+    /// de sauvegarde contient donc soit l'ancienne, soit la nouvelle version, jamais
+    /// une version partielle.
+    ///
+    /// Avec [`PersistentCache::spawn_writer`], la sauvegarde est confiée au thread
+    /// dédié et son éventuelle erreur est retournée par [`PersistentCache::sync`].
+    ///
+    /// Avec [`PersistentCache::set_retention`], la sauvegarde est ensuite copiée
+    /// dans une archive.
     ///
     /// ### Retourne
     /// - `Ok(())` : Si la sauvegarde réussit.
-    /// - `Err(io::Error)` : Si une erreur d'écriture se produit.
+    /// - `Err(StorageError::Io)` : Si une erreur d'écriture se produit.
     ///
     /// ### Exemple
-    /// ```rust
+    /// ```rust,no_run
+    /// # use cache_lru::storage::PersistentCache;
+    /// # let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
     /// cache.save().unwrap();
     /// ```
-    pub fn save(&self) -> io::Result<()> {
-        let mut file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&self.file_path)?;
-        for key in &self.keys {
-            if let Some(value) = self.map.get(key) {
-                writeln!(file, "{}:{}", key.to_string(), value.to_string())?;
+    pub fn save(&mut self) -> Result<(), StorageError> {
+        self.shared_write(|cache| {
+            cache.dirty_shards.fill(true);
+            cache.write_snapshot()?;
+            if cache.retention > 0 {
+                // L'archive est une copie de la sauvegarde qui vient d'être écrite.
+                cache.sink.sync()?;
+                writer::lock(&cache.store).archive(cache.retention, cache.archive_naming)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// ## Méthode `upgrade_in_place`
+    ///
+    /// Si une partie de la sauvegarde ou le journal est dans une version précédente
+    /// de son format, recharge le cache depuis le stockage puis réécrit toute la
+    /// sauvegarde dans la version actuelle, au format choisi par
+    /// [`PersistentCache::set_format`], et vide le journal. Les opérations en
+    /// attente sont d'abord écrites.
+    ///
+    /// ### Retourne
+    /// - `Ok(true)` : Si la sauvegarde a été réécrite.
+    /// - `Ok(false)` : Si tout était déjà dans la version actuelle.
+    /// - `Err(StorageError)` : Si une erreur de lecture ou d'écriture se produit,
+    ///   si une ligne est invalide ou si une version n'est pas prise en charge.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::storage::PersistentCache;
+    /// let mut cache: PersistentCache<String, String> = PersistentCache::open(3, "cache.txt").unwrap();
+    /// if cache.upgrade_in_place().unwrap() {
+    ///     println!("Sauvegarde réécrite dans la version actuelle");
+    /// }
+    /// ```
+    pub fn upgrade_in_place(&mut self) -> Result<bool, StorageError> {
+        self.shared_write(|cache| {
+            cache.write_pending(None)?;
+            cache.sink.sync()?;
+            let outdated = {
+                let store = writer::lock(&cache.store);
+                let mut outdated = false;
+                for shard in 0..cache.dirty_shards.len() {
+                    if let Some(snapshot) = store.read_snapshot(shard)? {
+                        let snapshot = transform::revert(&cache.transforms, &snapshot)?;
+                        outdated |= snapshot_outdated(&snapshot)?;
+                    }
+                }
+                if let Some(log) = store.read_log()? {
+                    outdated |= log_outdated(&log)?;
+                }
+                outdated
+            };
+            if !outdated {
+                return Ok(false);
+            }
+            cache.read_store(LoadOptions::strict())?;
+            cache.dirty_shards.fill(true);
+            cache.write_snapshot()?;
+            Ok(true)
+        })
+    }
+
+    // Réécrit les parties modifiées de la sauvegarde, puis vide le journal.
+    fn write_snapshot(&mut self) -> Result<(), StorageError> {
+        let cache_meta = CacheMeta {
+            capacity: self.cache.capacity(),
+            policy: "lru".to_string(),
+            stats: self.stats,
+        };
+        let mut files: Vec<Option<Vec<u8>>> = self
+            .dirty_shards
+            .iter()
+            .map(|&dirty| dirty.then(Vec::new))
+            .collect();
+        for file in files.iter_mut().flatten() {
+            match self.format {
+                SnapshotFormat::Text => {
+                    writeln!(file, "{}", format::header_with_meta(&cache_meta))?
+                }
+                SnapshotFormat::Binary => {
+                    file.write_all(&binary::header(cache_meta.encode().as_bytes()))?
+                }
+            }
+        }
+        for (key, entry) in self.cache.iter() {
+            let key = self.key_codec.encode(key);
+            let Some(file) = files[self.shard_of(&key)].as_mut() else {
+                continue;
+            };
+            let value = self.value_codec.encode(&entry.value);
+            let meta = entry.meta.encode();
+            match self.format {
+                SnapshotFormat::Text => writeln!(
+                    file,
+                    "{}",
+                    format::encode_record_with_meta(&key, &value, &meta)
+                )?,
+                SnapshotFormat::Binary => {
+                    file.write_all(&binary::encode_record(&key, &value, &meta))?
+                }
             }
         }
+        let shards = files
+            .into_iter()
+            .enumerate()
+            .filter_map(|(shard, file)| Some((shard, transform::apply(&self.transforms, file?))))
+            .collect();
+        self.sink.snapshot(&self.store, shards)?;
+
+        // La sauvegarde contient tout : le journal et les opérations en attente
+        // peuvent être vidés.
+        self.dirty_shards.fill(false);
+        self.pending.clear();
+        self.pending_keys.clear();
+        self.last_flush = Instant::now();
+        self.log_records = 0;
         Ok(())
     }
 
     /// ## Méthode `load`
     ///
-    /// Charge les données depuis le fichier défini lors de la création dans un cache.
-    /// Les clés et valeurs doivent être parsables depuis une chaîne de caractères.
+    /// Charge les données depuis le stockage défini lors de la création dans un cache,
+    /// puis rejoue le journal. Les clés et valeurs sont relues par les [`Codec`] du
+    /// cache. Les fichiers des versions précédentes du format sont aussi acceptés.
+    /// Les éléments expirés sont ignorés ; la capacité et les statistiques du
+    /// cache, ainsi que les tags des éléments, sont restaurés. Le chargement est strict : si une ligne est invalide,
+    /// rien n'est chargé. Le contenu actuel du cache est remplacé, et l'ordre
+    /// d'utilisation des éléments est celui du moment de la sauvegarde. Le
+    /// chargement n'écrit rien.
     ///
     /// ### Retourne
-    /// - `Ok(())` : Si le chargement réussit.
-    /// - `Err(io::Error)` : Si une erreur de lecture ou de parsing se produit.
+    /// - `Ok(LoadReport)` : Si le chargement réussit.
+    /// - `Err(StorageError)` : Si aucun fichier n'existe, si une erreur de lecture se
+    ///   produit, si une ligne est invalide ou si la version du fichier n'est pas
+    ///   prise en charge.
     ///
     /// ### Exemple
-    /// ```rust
+    /// ```rust,no_run
+    /// # use cache_lru::storage::PersistentCache;
+    /// # let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
     /// cache.load().unwrap();
     /// ```
-    pub fn load(&mut self) -> io::Result<()> {
-        let content = fs::read_to_string(&self.file_path)?;
-        for line in content.lines() {
-            if let Some((key, value)) = line.split_once(':') {
-                let key = key.parse::<K>().expect("Clé invalide");
-                let value = value.parse::<V>().expect("Valeur invalide");
-                self.put(key, value);
+    pub fn load(&mut self) -> Result<LoadReport, StorageError> {
+        self.load_with(LoadOptions::strict())
+    }
+
+    /// ## Méthode `load_with`
+    ///
+    /// Identique à [`PersistentCache::load`], avec des options de chargement.
+    ///
+    /// ### Arguments
+    /// - `options` : Les options de chargement.
+    ///
+    /// ### Retourne
+    /// - `Ok(LoadReport)` : Le nombre d'éléments chargés et les lignes ignorées.
+    /// - `Err(StorageError)` : Si aucun fichier n'existe, si une erreur de lecture se
+    ///   produit, si la version d'un fichier n'est pas prise en charge, ou si une ligne
+    ///   est invalide en mode strict.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::storage::{LoadOptions, PersistentCache};
+    /// # let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// let report = cache.load_with(LoadOptions::skip_bad_lines()).unwrap();
+    /// for bad_line in &report.skipped {
+    ///     println!("Ligne ignorée : {}", bad_line);
+    /// }
+    /// ```
+    pub fn load_with(&mut self, options: LoadOptions) -> Result<LoadReport, StorageError> {
+        if self.sharing == Sharing::Private {
+            return self.read_store(options);
+        }
+        writer::lock(&self.store).lock(false)?;
+        let result = self.read_store(options);
+        self.unlock(result)
+    }
+
+    // Relit le stockage, sans verrou.
+    fn read_store(&mut self, options: LoadOptions) -> Result<LoadReport, StorageError> {
+        // Les écritures déjà confiées au thread dédié doivent être terminées.
+        self.sink.sync()?;
+        let (snapshots, log) = {
+            let store = writer::lock(&self.store);
+            let snapshots = (0..self.dirty_shards.len())
+                .map(|shard| store.read_snapshot(shard))
+                .collect::<io::Result<Vec<_>>>()?;
+            (snapshots, store.read_log()?)
+        };
+        if snapshots.iter().all(Option::is_none) && log.is_none() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "Aucune sauvegarde").into());
+        }
+
+        let mut report = LoadReport::default();
+        let mut shards = Vec::new();
+        for snapshot in snapshots.iter().flatten() {
+            let snapshot = transform::revert(&self.transforms, snapshot)?;
+            shards.push(if binary::is_binary(&snapshot) {
+                self.parse_binary_snapshot(&snapshot, options, &mut report)?
+            } else {
+                self.parse_snapshot(utf8(&snapshot)?, options, &mut report)?
+            });
+        }
+        let entries = interleave(shards);
+        let records = match &log {
+            Some(bytes) => {
+                // Une dernière ligne sans retour à la ligne correspond à une écriture
+                // interrompue et est ignorée.
+                let end = bytes
+                    .iter()
+                    .rposition(|&byte| byte == b'\n')
+                    .map_or(0, |end| end + 1);
+                self.parse_log(utf8(&bytes[..end])?, options, &mut report)?
+            }
+            None => Vec::new(),
+        };
+
+        match options.mode {
+            LoadMode::Replace => {
+                // Les opérations en attente concernent le contenu remplacé. Le cache
+                // reprend la capacité et les statistiques enregistrées.
+                let capacity = report
+                    .cache_meta
+                    .as_ref()
+                    .map_or(self.cache.capacity(), |cache_meta| cache_meta.capacity);
+                self.cache = Cache::new(capacity);
+                if let Some(cache_meta) = &report.cache_meta {
+                    self.stats = cache_meta.stats;
+                }
+                self.pending.clear();
+                self.pending_keys.clear();
+                self.dirty_shards.fill(false);
+            }
+            // Le contenu fusionné ne correspond plus à aucune partie de la sauvegarde.
+            LoadMode::Merge => self.dirty_shards.fill(true),
+        }
+        // La sauvegarde liste les éléments du plus récent au plus ancien : ils sont
+        // insérés dans l'ordre inverse pour retrouver le même ordre d'utilisation.
+        let now = SystemTime::now();
+        for (key, value, meta) in entries.into_iter().rev() {
+            if meta.is_expired(now) {
+                report.expired += 1;
+                // L'élément disparaîtra de sa partie à la prochaine compaction.
+                self.mark_shard(&self.key_codec.encode(&key));
+                continue;
+            }
+            report.loaded += 1;
+            self.replay(Replayed::Put(key, value, meta), false);
+        }
+        // Les parties concernées par le journal diffèrent de leur sauvegarde.
+        self.log_records = records.len();
+        for record in records {
+            match record {
+                Replayed::Put(key, _, meta) if meta.is_expired(now) => {
+                    report.expired += 1;
+                    self.replay(Replayed::Remove(key), true);
+                }
+                record => self.replay(record, true),
+            }
+        }
+        if self.sharing != Sharing::Private {
+            self.seen_generation = writer::lock(&self.store).generation()?;
+        }
+        Ok(report)
+    }
+
+    // Exécute une écriture. Avec un stockage partagé, elle a lieu sous verrou
+    // exclusif, après un éventuel rechargement.
+    fn shared_write<T>(
+        &mut self,
+        write: impl FnOnce(&mut Self) -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
+        if self.sharing == Sharing::Private {
+            return write(self);
+        }
+        writer::lock(&self.store).lock(true)?;
+        let result = self.reload_if_changed().and_then(|()| {
+            let value = write(self)?;
+            // Le thread dédié doit avoir terminé avant que le verrou soit libéré.
+            self.sink.sync()?;
+            self.seen_generation = writer::lock(&self.store).generation()?;
+            Ok(value)
+        });
+        self.unlock(result)
+    }
+
+    fn reload_if_changed(&mut self) -> Result<(), StorageError> {
+        let Sharing::ReloadOnChange(mode) = self.sharing else {
+            return Ok(());
+        };
+        if writer::lock(&self.store).generation()? == self.seen_generation {
+            return Ok(());
+        }
+        // Les opérations en attente sont écrites à la suite de celles de l'autre
+        // processus, pour être relues avec elles.
+        self.write_pending(None)?;
+        match self.read_store(LoadOptions::strict().with_mode(mode)) {
+            Ok(_) => Ok(()),
+            Err(StorageError::Io(err)) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    // Libère le verrou du stockage. L'erreur de `result` est prioritaire.
+    fn unlock<T>(&self, result: Result<T, StorageError>) -> Result<T, StorageError> {
+        let unlocked = writer::lock(&self.store).unlock();
+        let value = result?;
+        unlocked?;
+        Ok(value)
+    }
+
+    // Applique un élément relu au cache et marque les parties modifiées : celle
+    // d'un élément retiré faute de place, et celle de la clé si `logged` est vrai.
+    fn replay(&mut self, record: Replayed<K, V>, logged: bool) {
+        let key = match record {
+            Replayed::Put(key, value, meta) => {
+                if let Some((evicted, _)) = push_entry(&mut self.cache, key.clone(), value, meta) {
+                    self.mark_shard(&self.key_codec.encode(&evicted));
+                }
+                key
+            }
+            Replayed::Remove(key) => {
+                self.cache.remove(&key);
+                key
+            }
+            Replayed::Touch(key) => {
+                self.cache.get(&key);
+                key
+            }
+        };
+        if logged {
+            self.mark_shard(&self.key_codec.encode(&key));
+        }
+    }
+
+    // Lit les éléments du fichier de sauvegarde.
+    fn parse_snapshot(
+        &self,
+        content: &str,
+        options: LoadOptions,
+        report: &mut LoadReport,
+    ) -> Result<Vec<(K, V, EntryMeta)>, StorageError> {
+        let mut lines = content.lines().enumerate().peekable();
+        let version = match lines
+            .peek()
+            .and_then(|(_, line)| format::parse_header(line))
+        {
+            Some(version) => {
+                let (_, line) = lines.next().unwrap_or_default();
+                Self::record_cache_meta(format::parse_header_meta(line), report);
+                version
+            }
+            None => 1,
+        };
+        let migrations = &self.snapshot_migrations;
+        migrations.check(version)?;
+
+        let mut entries = Vec::new();
+        for (index, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let record = if version == migrations.current() {
+                format::decode_record_with_meta(line)
+            } else {
+                migrations
+                    .upgrade_line(version, line)
+                    .and_then(|line| format::decode_record_with_meta(&line))
+            };
+            let parsed = record
+                .ok_or_else(|| "ligne mal formée".to_string())
+                .and_then(|(key, value, meta)| self.parse_entry(key, value, &meta));
+            match parsed {
+                Ok(entry) => entries.push(entry),
+                Err(reason) => Self::bad_line(index, line, reason, options, report)?,
+            }
+        }
+        Ok(entries)
+    }
+
+    // Lit les éléments d'un fichier de sauvegarde binaire. Les éléments sont
+    // numérotés comme des lignes à partir de 1.
+    fn parse_binary_snapshot(
+        &self,
+        bytes: &[u8],
+        options: LoadOptions,
+        report: &mut LoadReport,
+    ) -> Result<Vec<(K, V, EntryMeta)>, StorageError> {
+        let decoded = binary::decode(bytes)?;
+        report.discarded_bytes += decoded.discarded_bytes;
+        let cache_meta = std::str::from_utf8(&decoded.cache_meta)
+            .ok()
+            .and_then(CacheMeta::decode);
+        Self::record_cache_meta(cache_meta, report);
+
+        let mut entries = Vec::new();
+        for (index, (key, value, meta)) in decoded.records.into_iter().enumerate() {
+            let text = format::encode_record_with_meta(&key, &value, &meta);
+            match self.parse_entry(key, value, &meta) {
+                Ok(entry) => entries.push(entry),
+                Err(reason) => Self::bad_line(index, &text, reason, options, report)?,
             }
         }
+        Ok(entries)
+    }
+
+    // Lit les opérations complètes du journal.
+    fn parse_log(
+        &self,
+        content: &str,
+        options: LoadOptions,
+        report: &mut LoadReport,
+    ) -> Result<Vec<Replayed<K, V>>, StorageError> {
+        let mut lines = content.lines().enumerate().peekable();
+        let migrations = &self.log_migrations;
+        let mut version = migrations.current();
+        if let Some(found) = lines
+            .peek()
+            .and_then(|(_, line)| format::parse_log_header(line))
+        {
+            migrations.check(found)?;
+            version = found;
+            lines.next();
+        }
+
+        // Les lots transformés sont remplacés par leurs lignes, qui gardent le numéro
+        // de ligne du lot.
+        let mut expanded = Vec::new();
+        for (index, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            match transform::revert_batch(&self.transforms, line) {
+                Ok(Some(batch)) => {
+                    expanded.extend(batch.lines().map(|line| (index, line.to_string())))
+                }
+                Ok(None) => expanded.push((index, line.to_string())),
+                Err(err) if !options.skip_bad_lines => return Err(err),
+                Err(err) => Self::bad_line(index, line, err.to_string(), options, report)?,
+            }
+        }
+
+        let mut records = Vec::new();
+        for (index, line) in expanded.iter().filter(|(_, line)| !line.is_empty()) {
+            let (index, line) = (*index, line.as_str());
+            let record = if version == migrations.current() {
+                format::decode_log_record(line)
+            } else {
+                migrations
+                    .upgrade_line(version, line)
+                    .and_then(|line| format::decode_log_record(&line))
+            };
+            let parsed = match record {
+                Some(LogRecord::Put(key, value, meta)) => self
+                    .parse_entry(key, value, &meta)
+                    .map(|(key, value, meta)| Replayed::Put(key, value, meta)),
+                Some(LogRecord::Remove(key)) => self.parse_key(key).map(Replayed::Remove),
+                Some(LogRecord::Touch(key)) => self.parse_key(key).map(Replayed::Touch),
+                None => Err("opération du journal mal formée".to_string()),
+            };
+            match parsed {
+                Ok(record) => records.push(record),
+                Err(reason) => {
+                    let reason = format!("journal, {}", reason);
+                    Self::bad_line(index, line, reason, options, report)?
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    // Signale une ligne invalide : erreur en mode strict, ligne ignorée sinon.
+    fn bad_line(
+        index: usize,
+        line: &str,
+        reason: String,
+        options: LoadOptions,
+        report: &mut LoadReport,
+    ) -> Result<(), StorageError> {
+        let bad_line = BadLine {
+            line: index + 1,
+            text: line.to_string(),
+            reason,
+        };
+        if !options.skip_bad_lines {
+            return Err(StorageError::Parse(bad_line));
+        }
+        report.skipped.push(bad_line);
         Ok(())
     }
 
+    // Conserve les métadonnées du cache les plus récentes parmi les parties de la
+    // sauvegarde.
+    fn record_cache_meta(cache_meta: Option<CacheMeta>, report: &mut LoadReport) {
+        report.cache_meta = match (report.cache_meta.take(), cache_meta) {
+            (Some(current), Some(other)) => Some(current.latest(other)),
+            (current, other) => current.or(other),
+        };
+    }
+
+    // Lit un élément. Sans métadonnées (versions précédentes du format), l'élément
+    // est considéré comme inséré maintenant.
+    fn parse_entry(
+        &self,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        meta: &[u8],
+    ) -> Result<(K, V, EntryMeta), String> {
+        let key = self.parse_key(key)?;
+        let value = self
+            .value_codec
+            .decode(&value)
+            .map_err(|reason| format!("valeur invalide : {}", reason))?;
+        let meta = if meta.is_empty() {
+            EntryMeta::new()
+        } else {
+            EntryMeta::decode(meta).ok_or_else(|| "métadonnées invalides".to_string())?
+        };
+        Ok((key, value, meta))
+    }
+
+    fn parse_key(&self, key: Vec<u8>) -> Result<K, String> {
+        self.key_codec
+            .decode(&key)
+            .map_err(|reason| format!("clé invalide : {}", reason))
+    }
+
     /// ## Méthode `put`
     ///
     /// Ajoute une clé et une valeur au cache. Si la clé existe déjà,
     /// elle est mise à jour. Si la capacité est atteinte, le plus ancien
-    /// élément est supprimé. La sauvegarde est effectuée automatiquement.
+    /// élément est supprimé. L'opération est enregistrée automatiquement.
     ///
     /// ### Arguments
     /// - `key` : La clé de l'élément.
     /// - `value` : La valeur associée.
     ///
+    /// ### Retourne
+    /// - `Ok(())` : Si l'ajout et l'enregistrement réussissent.
+    /// - `Err(StorageError)` : Si l'enregistrement échoue ; l'élément reste dans le cache.
+    ///
     /// ### Exemple
-    /// ```rust
-    /// cache.put("A".to_string(), "Valeur A".to_string());
+    /// ```rust,no_run
+    /// # use cache_lru::storage::PersistentCache;
+    /// # let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// cache.put("A".to_string(), "Valeur A".to_string()).unwrap();
     /// ```
-    pub fn put(&mut self, key: K, value: V) {
-        if self.map.contains_key(&key) {
-            self.keys.retain(|k| k != &key);
-        } else if self.keys.len() >= self.capacity {
-            if let Some(old_key) = self.keys.pop() {
-                self.map.remove(&old_key);
+    pub fn put(&mut self, key: K, value: V) -> Result<(), StorageError> {
+        self.push(key, value).map(|_| ())
+    }
+
+    /// ## Méthode `push`
+    ///
+    /// Identique à [`PersistentCache::put`], mais retourne l'élément retiré du
+    /// cache lorsque la capacité est atteinte.
+    ///
+    /// ### Retourne
+    /// - `Ok(Some((K, V)))` : Le plus ancien élément, s'il a été retiré.
+    /// - `Ok(None)` : Si aucun élément n'a été retiré.
+    /// - `Err(StorageError)` : Si l'enregistrement échoue ; l'élément reste dans le cache.
+    pub fn push(&mut self, key: K, value: V) -> Result<Option<(K, V)>, StorageError> {
+        self.push_with_meta(key, value, EntryMeta::new())
+    }
+
+    /// ## Méthode `put_with_meta`
+    ///
+    /// Identique à [`PersistentCache::put`], avec les métadonnées de l'élément :
+    /// son expiration, son poids et ses tags. Elles sont enregistrées avec
+    /// l'élément et restaurées au chargement. Un élément expiré est considéré
+    /// comme absent.
+    ///
+    /// ### Arguments
+    /// - `key` : La clé de l'élément.
+    /// - `value` : La valeur associée.
+    /// - `meta` : Les métadonnées de l'élément.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::storage::{EntryMeta, PersistentCache};
+    /// # use std::time::Duration;
+    /// # let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// let meta = EntryMeta::new()
+    ///     .with_ttl(Duration::from_secs(60))
+    ///     .with_tags(&["session"]);
+    /// cache.put_with_meta("A".to_string(), "Valeur A".to_string(), meta).unwrap();
+    /// ```
+    pub fn put_with_meta(&mut self, key: K, value: V, meta: EntryMeta) -> Result<(), StorageError> {
+        self.push_with_meta(key, value, meta).map(|_| ())
+    }
+
+    /// ## Méthode `push_with_meta`
+    ///
+    /// Identique à [`PersistentCache::put_with_meta`], mais retourne l'élément
+    /// retiré du cache lorsque la capacité est atteinte.
+    pub fn push_with_meta(
+        &mut self,
+        key: K,
+        value: V,
+        meta: EntryMeta,
+    ) -> Result<Option<(K, V)>, StorageError> {
+        self.shared_write(|cache| {
+            let encoded = cache.key_codec.encode(&key);
+            let record = LogRecord::Put(
+                encoded.clone(),
+                cache.value_codec.encode(&value),
+                meta.encode(),
+            );
+            let evicted = push_entry(&mut cache.cache, key, value, meta)
+                .map(|(evicted_key, entry)| (evicted_key, entry.value));
+            cache.stats.insertions += 1;
+            if let Some((evicted_key, _)) = &evicted {
+                cache.stats.evictions += 1;
+                cache.mark_shard(&cache.key_codec.encode(evicted_key));
             }
-        }
-        self.keys.insert(0, key.clone());
-        self.map.insert(key, value);
-        self.save().unwrap();
+            cache.append(encoded, record)?;
+            Ok(evicted)
+        })
     }
 
     /// ## Méthode `get`
     ///
     /// Récupère une valeur depuis sa clé. Si la clé est trouvée, elle est
-    /// marquée comme récemment utilisée.
+    /// marquée comme récemment utilisée. Ce changement d'ordre n'est enregistré
+    /// qu'avec [`PersistentCache::set_track_reads`] : sinon, utiliser
+    /// [`PersistentCache::touch`] pour le conserver.
+    ///
+    /// Un élément expiré est retiré du cache et n'est pas retourné. La lecture est
+    /// comptée dans les statistiques du cache et, si la clé est trouvée, dans le
+    /// nombre d'accès de l'élément. Sans [`PersistentCache::set_track_reads`], ce
+    /// nombre n'est enregistré qu'avec la prochaine modification de la partie de la
+    /// sauvegarde qui contient l'élément.
     ///
     /// ### Arguments
     /// - `key` : La clé de l'élément à récupérer.
@@ -148,18 +1321,391 @@ where
     /// - `None` : Si la clé n'existe pas dans le cache.
     ///
     /// ### Exemple
-    /// ```rust
+    /// ```rust,no_run
+    /// # use cache_lru::storage::PersistentCache;
+    /// # let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
     /// if let Some(value) = cache.get(&"A".to_string()) {
     ///     println!("Valeur : {}", value);
     /// }
     /// ```
     pub fn get(&mut self, key: &K) -> Option<&V> {
-        if self.map.contains_key(key) {
-            self.keys.retain(|k| k != key);
-            self.keys.insert(0, key.clone());
-            self.map.get(key)
-        } else {
-            None
+        if !self.expire(key) {
+            self.stats.misses += 1;
+            return None;
+        }
+        self.stats.hits += 1;
+        // Sans suivi des lectures, le nombre d'accès n'est enregistré qu'à la
+        // prochaine réécriture de la partie de la sauvegarde de l'élément.
+        if self.track_reads {
+            let encoded = self.key_codec.encode(key);
+            self.mark_shard(&encoded);
+            if self.write_mode == WriteMode::WriteBack {
+                self.mark_dirty(encoded.clone(), LogRecord::Touch(encoded));
+            }
+        }
+        self.cache.get(key);
+        let entry = self.cache.peek_mut(key)?;
+        entry.meta.access_count += 1;
+        Some(&entry.value)
+    }
+
+    /// ## Méthode `metadata`
+    ///
+    /// Retourne les métadonnées d'un élément, sans modifier l'ordre d'accès.
+    ///
+    /// ### Retourne
+    /// - `Some(&EntryMeta)` : Les métadonnées si la clé est présente et non expirée.
+    /// - `None` : Sinon.
+    pub fn metadata(&self, key: &K) -> Option<&EntryMeta> {
+        self.cache
+            .peek(key)
+            .map(|entry| &entry.meta)
+            .filter(|meta| !meta.is_expired(SystemTime::now()))
+    }
+
+    /// ## Méthode `stats`
+    ///
+    /// Retourne les statistiques cumulées du cache. Elles sont enregistrées avec
+    /// la sauvegarde et restaurées au chargement.
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    // Retire un élément expiré du cache, sans l'enregistrer : son expiration est
+    // enregistrée avec lui. Retourne `true` si la clé est présente et non expirée.
+    fn expire(&mut self, key: &K) -> bool {
+        match self.cache.peek(key) {
+            None => false,
+            Some(entry) if entry.meta.is_expired(SystemTime::now()) => {
+                self.cache.remove(key);
+                self.mark_shard(&self.key_codec.encode(key));
+                false
+            }
+            Some(_) => true,
+        }
+    }
+
+    /// ## Méthode `touch`
+    ///
+    /// Marque une clé comme récemment utilisée et enregistre ce changement d'ordre.
+    ///
+    /// ### Retourne
+    /// - `Ok(true)` : Si la clé est présente.
+    /// - `Ok(false)` : Si la clé n'existe pas dans le cache.
+    /// - `Err(StorageError)` : Si l'enregistrement échoue.
+    pub fn touch(&mut self, key: &K) -> Result<bool, StorageError> {
+        self.shared_write(|cache| {
+            if !cache.expire(key) {
+                return Ok(false);
+            }
+            cache.cache.get(key);
+            let encoded = cache.key_codec.encode(key);
+            cache.append(encoded.clone(), LogRecord::Touch(encoded))?;
+            Ok(true)
+        })
+    }
+
+    /// ## Méthode `remove`
+    ///
+    /// Retire une clé du cache. L'opération est enregistrée automatiquement.
+    ///
+    /// ### Arguments
+    /// - `key` : La clé de l'élément à retirer.
+    ///
+    /// ### Retourne
+    /// - `Ok(Some(V))` : La valeur retirée.
+    /// - `Ok(None)` : Si la clé n'existe pas dans le cache ou si l'élément est expiré.
+    /// - `Err(StorageError)` : Si l'enregistrement échoue ; la clé reste retirée du cache.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::storage::PersistentCache;
+    /// # let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// cache.remove(&"A".to_string()).unwrap();
+    /// ```
+    pub fn remove(&mut self, key: &K) -> Result<Option<V>, StorageError> {
+        self.shared_write(|cache| {
+            let Some(entry) = cache.cache.remove(key) else {
+                return Ok(None);
+            };
+            let encoded = cache.key_codec.encode(key);
+            cache.append(encoded.clone(), LogRecord::Remove(encoded))?;
+            Ok((!entry.meta.is_expired(SystemTime::now())).then_some(entry.value))
+        })
+    }
+
+    /// ## Méthode `invalidate_tag`
+    ///
+    /// Retire tous les éléments portant un tag, y compris après un rechargement :
+    /// les tags font partie des métadonnées enregistrées.
+    ///
+    /// ### Retourne
+    /// - `Ok(usize)` : Le nombre d'éléments retirés.
+    /// - `Err(StorageError)` : Si l'écriture dans le journal échoue.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::storage::{EntryMeta, PersistentCache};
+    /// # let mut cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// let meta = EntryMeta::new().with_tags(&["user:42"]);
+    /// cache.put_with_meta("profil".to_string(), "Profil".to_string(), meta).unwrap();
+    /// assert_eq!(cache.invalidate_tag("user:42").unwrap(), 1);
+    /// ```
+    pub fn invalidate_tag(&mut self, tag: &str) -> Result<usize, StorageError> {
+        self.shared_write(|cache| {
+            let keys: Vec<K> = cache.cache.tagged(tag).cloned().collect();
+            for key in &keys {
+                cache.cache.remove(key);
+                let encoded = cache.key_codec.encode(key);
+                cache.append(encoded.clone(), LogRecord::Remove(encoded))?;
+            }
+            Ok(keys.len())
+        })
+    }
+
+    /// ## Méthode `contains`
+    ///
+    /// Indique si une clé est présente dans le cache et non expirée, sans modifier
+    /// l'ordre d'accès.
+    pub fn contains(&self, key: &K) -> bool {
+        self.metadata(key).is_some()
+    }
+
+    /// ## Méthode `export`
+    ///
+    /// Écrit les éléments non expirés du cache, du plus récemment au moins
+    /// récemment utilisé, au format JSON Lines ou CSV décrit dans le module
+    /// [`exchange`]. Les métadonnées des éléments ne sont pas exportées.
+    ///
+    /// ### Exemple
+    /// ```rust,no_run
+    /// # use cache_lru::exchange::ExchangeFormat;
+    /// # use cache_lru::storage::PersistentCache;
+    /// # let cache: PersistentCache<String, String> = PersistentCache::new(3, "cache.txt");
+    /// let file = std::fs::File::create("cache.csv").unwrap();
+    /// cache.export(file, ExchangeFormat::Csv).unwrap();
+    /// ```
+    pub fn export(&self, writer: impl Write, format: ExchangeFormat) -> io::Result<()>
+    where
+        K: Display,
+        V: Display,
+    {
+        let now = SystemTime::now();
+        let entries = self
+            .cache
+            .iter()
+            .filter(|(_, entry)| !entry.meta.is_expired(now))
+            .map(|(key, entry)| (key, &entry.value));
+        exchange::write_entries(writer, format, entries)
+    }
+
+    /// ## Méthode `import`
+    ///
+    /// Identique à [`Cache::import`] : chaque élément importé est ajouté comme par
+    /// [`PersistentCache::put`], et enregistré automatiquement.
+    ///
+    /// ### Retourne
+    /// - `Ok(ImportReport)` : Le nombre d'éléments importés et ignorés.
+    /// - `Err(StorageError)` : Si la lecture échoue ou si une ligne est invalide,
+    ///   auquel cas rien n'est importé, ou si un enregistrement échoue.
+    pub fn import(
+        &mut self,
+        reader: impl Read,
+        format: ExchangeFormat,
+        overflow: Overflow,
+    ) -> Result<ImportReport, StorageError>
+    where
+        K: FromStr,
+        V: FromStr,
+        <K as FromStr>::Err: Debug,
+        <V as FromStr>::Err: Debug,
+    {
+        let entries = exchange::read_entries(reader, format)?;
+        let (entries, dropped) =
+            exchange::select(entries, overflow, self.cache.capacity(), self.cache.len());
+        let imported = entries.len();
+        for (key, value) in entries {
+            self.put(key, value)?;
         }
+        Ok(ImportReport { imported, dropped })
     }
+
+    // Enregistre une opération sur une clé encodée : immédiatement, ou en différé selon
+    // le mode d'écriture. Le journal est ensuite compacté si le seuil est atteint.
+    fn append(&mut self, key: Vec<u8>, record: LogRecord) -> Result<(), StorageError> {
+        self.mark_shard(&key);
+        match self.write_mode {
+            WriteMode::WriteThrough => self.write_pending(Some(record))?,
+            WriteMode::WriteBack => {
+                self.mark_dirty(key, record);
+                let expired = self
+                    .flush_interval
+                    .is_some_and(|interval| self.last_flush.elapsed() >= interval);
+                if self.pending_keys.len() >= self.flush_threshold || expired {
+                    self.write_pending(None)?;
+                }
+            }
+        }
+        self.compact_if_needed()
+    }
+
+    fn compact_if_needed(&mut self) -> Result<(), StorageError> {
+        if self.log_records >= self.compaction_threshold {
+            self.write_snapshot()?;
+        }
+        Ok(())
+    }
+}
+
+impl<K, V, KC, VC> PersistentCache<K, V, KC, VC> {
+    // Partie de la sauvegarde d'une clé encodée. La répartition ne dépend que des
+    // octets de la clé, pour rester la même d'une exécution à l'autre.
+    fn shard_of(&self, key: &[u8]) -> usize {
+        match self.dirty_shards.len() {
+            1 => 0,
+            shards => binary::crc32(key) as usize % shards,
+        }
+    }
+
+    fn mark_shard(&mut self, key: &[u8]) {
+        let shard = self.shard_of(key);
+        self.dirty_shards[shard] = true;
+    }
+
+    // Met une opération en attente. Elle remplace l'opération en attente sur la même
+    // clé et passe en dernière position ; une utilisation conserve l'ajout en attente.
+    fn mark_dirty(&mut self, key: Vec<u8>, record: LogRecord) {
+        let previous = self
+            .pending_keys
+            .get(&key)
+            .and_then(|&index| self.pending[index].take());
+        let record = match (record, previous) {
+            (LogRecord::Touch(_), Some(put @ LogRecord::Put(..))) => put,
+            (record, _) => record,
+        };
+        self.pending_keys.insert(key, self.pending.len());
+        self.pending.push(Some(record));
+    }
+
+    // Écrit en une seule fois les opérations en attente, suivies de `record`. En cas
+    // d'échec, les opérations en attente sont conservées.
+    fn write_pending(&mut self, record: Option<LogRecord>) -> Result<(), StorageError> {
+        let mut lines = String::new();
+        let mut count = 0;
+        for record in self.pending.iter().flatten().chain(record.as_ref()) {
+            lines.push_str(&format::encode_log_record(record));
+            lines.push('\n');
+            count += 1;
+        }
+        if count > 0 {
+            // Le lot est transformé d'un seul tenant, comme une sauvegarde.
+            if !self.transforms.is_empty() {
+                lines = transform::apply_batch(&self.transforms, &lines);
+                lines.push('\n');
+            }
+            self.sink.append(&self.store, lines.into_bytes())?;
+            self.log_records += count;
+        }
+        self.pending.clear();
+        self.pending_keys.clear();
+        self.last_flush = Instant::now();
+        Ok(())
+    }
+}
+
+// Les opérations en attente sont écrites à la destruction du cache. Une erreur
+// d'écriture ne peut pas être signalée ici : appeler `flush` pour la récupérer.
+impl<K, V, KC, VC> Drop for PersistentCache<K, V, KC, VC> {
+    fn drop(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        if self.sharing == Sharing::Private {
+            let _ = self.write_pending(None);
+        } else if writer::lock(&self.store).lock(true).is_ok() {
+            let _ = self.write_pending(None);
+            let _ = self.sink.sync();
+            let _ = writer::lock(&self.store).unlock();
+        }
+    }
+}
+
+// Valeur d'un élément du cache et ses métadonnées.
+#[derive(Debug)]
+struct Entry<V> {
+    value: V,
+    meta: EntryMeta,
+}
+
+// Opération du journal, une fois la clé et la valeur lues.
+enum Replayed<K, V> {
+    Put(K, V, EntryMeta),
+    Remove(K),
+    Touch(K),
+}
+
+// Fusionne les éléments des parties de la sauvegarde, listés chacun du plus récent
+// au plus ancien, en alternant entre les parties pour approcher l'ordre d'origine.
+// Ajoute un élément au cache, avec ses tags pour que `invalidate_tag` le retrouve.
+fn push_entry<K: Eq + std::hash::Hash + Clone, V>(
+    cache: &mut Cache<K, Entry<V>>,
+    key: K,
+    value: V,
+    meta: EntryMeta,
+) -> Option<(K, Entry<V>)> {
+    let tags = meta.tags.clone();
+    let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+    cache.push_tagged(key, Entry { value, meta }, &tags)
+}
+
+fn interleave<T>(shards: Vec<Vec<T>>) -> Vec<T> {
+    let mut shards: Vec<_> = shards.into_iter().map(Vec::into_iter).collect();
+    let mut entries = Vec::new();
+    loop {
+        let before = entries.len();
+        entries.extend(shards.iter_mut().filter_map(Iterator::next));
+        if entries.len() == before {
+            return entries;
+        }
+    }
+}
+
+// Indique si une partie de la sauvegarde est dans une version précédente de son
+// format. Une partie vide n'a pas de version.
+fn snapshot_outdated(snapshot: &[u8]) -> Result<bool, StorageError> {
+    let (version, supported) = if binary::is_binary(snapshot) {
+        let version = snapshot.get(4..8).and_then(|bytes| bytes.try_into().ok());
+        (version.map(u32::from_le_bytes), binary::VERSION)
+    } else {
+        let version = first_line(snapshot).map(|line| format::parse_header(line).unwrap_or(1));
+        (version, format::VERSION)
+    };
+    outdated(version, supported)
+}
+
+fn log_outdated(log: &[u8]) -> Result<bool, StorageError> {
+    outdated(
+        first_line(log).and_then(format::parse_log_header),
+        format::LOG_VERSION,
+    )
+}
+
+fn outdated(version: Option<u32>, supported: u32) -> Result<bool, StorageError> {
+    match version {
+        Some(found) if found > supported => {
+            Err(StorageError::UnsupportedVersion { found, supported })
+        }
+        version => Ok(version.is_some_and(|version| version < supported)),
+    }
+}
+
+fn first_line(bytes: &[u8]) -> Option<&str> {
+    let line = bytes.split(|&byte| byte == b'\n').next()?;
+    std::str::from_utf8(line)
+        .ok()
+        .filter(|line| !line.is_empty())
+}
+
+fn utf8(bytes: &[u8]) -> io::Result<&str> {
+    std::str::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
 }
@@ -0,0 +1,151 @@
+//! Conversion des clés et des valeurs d'un [`PersistentCache`](super::PersistentCache)
+//! en octets.
+//!
+//! ## Fonctionnalités principales
+//! - Définir sa propre conversion avec le trait [`Codec`].
+//! - Utiliser la représentation textuelle d'un type avec [`StringCodec`].
+//! - Stocker des octets bruts avec [`BytesCodec`].
+//! - Stocker des entiers sur une taille fixe avec [`IntCodec`].
+
+use std::fmt::Debug;
+use std::str::FromStr;
+
+/// # Trait `Codec`
+///
+/// Ce trait convertit une valeur de type `T` en octets, et inversement. Les octets
+/// produits par [`Codec::encode`] doivent être relus à l'identique par [`Codec::decode`].
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::codec::Codec;
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// struct PointCodec;
+///
+/// impl Codec<Point> for PointCodec {
+///     fn encode(&self, point: &Point) -> Vec<u8> {
+///         let mut bytes = point.x.to_le_bytes().to_vec();
+///         bytes.extend_from_slice(&point.y.to_le_bytes());
+///         bytes
+///     }
+///
+///     fn decode(&self, bytes: &[u8]) -> Result<Point, String> {
+///         let (x, y) = bytes.split_at_checked(4).ok_or("point trop court")?;
+///         Ok(Point {
+///             x: i32::from_le_bytes(x.try_into().map_err(|_| "x invalide")?),
+///             y: i32::from_le_bytes(y.try_into().map_err(|_| "y invalide")?),
+///         })
+///     }
+/// }
+/// ```
+pub trait Codec<T> {
+    /// Convertit une valeur en octets.
+    fn encode(&self, value: &T) -> Vec<u8>;
+
+    /// Relit une valeur depuis ses octets, ou retourne la raison de l'échec.
+    fn decode(&self, bytes: &[u8]) -> Result<T, String>;
+}
+
+/// # Structure `StringCodec`
+///
+/// Utilise la représentation textuelle d'un type (`ToString` et `FromStr`),
+/// encodée en UTF-8. C'est la conversion par défaut d'un `PersistentCache`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StringCodec;
+
+impl<T> Codec<T> for StringCodec
+where
+    T: ToString + FromStr,
+    <T as FromStr>::Err: Debug,
+{
+    fn encode(&self, value: &T) -> Vec<u8> {
+        value.to_string().into_bytes()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<T, String> {
+        let text = std::str::from_utf8(bytes).map_err(|err| format!("UTF-8 invalide : {}", err))?;
+        text.parse::<T>().map_err(|err| format!("{:?}", err))
+    }
+}
+
+/// # Structure `BytesCodec`
+///
+/// Stocke des octets bruts (`Vec<u8>`) sans conversion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BytesCodec;
+
+impl Codec<Vec<u8>> for BytesCodec {
+    fn encode(&self, value: &Vec<u8>) -> Vec<u8> {
+        value.clone()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        Ok(bytes.to_vec())
+    }
+}
+
+/// # Structure `IntCodec`
+///
+/// Stocke un entier sur sa taille fixe, en petit-boutiste. Les `usize` et `isize`,
+/// dont la taille dépend de la plateforme, sont stockés comme des `u64` et des
+/// `i64` : une valeur trop grande pour la plateforme qui la relit est refusée.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::codec::{Codec, IntCodec};
+/// assert_eq!(IntCodec.encode(&258u16), vec![2, 1]);
+/// assert_eq!(Codec::<u16>::decode(&IntCodec, &[2, 1]), Ok(258));
+/// assert_eq!(IntCodec.encode(&258usize), vec![2, 1, 0, 0, 0, 0, 0, 0]);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IntCodec;
+
+macro_rules! int_codec {
+    ($($int:ty),*) => {
+        $(
+            impl Codec<$int> for IntCodec {
+                fn encode(&self, value: &$int) -> Vec<u8> {
+                    value.to_le_bytes().to_vec()
+                }
+
+                fn decode(&self, bytes: &[u8]) -> Result<$int, String> {
+                    let bytes = bytes.try_into().map_err(|_| {
+                        format!(
+                            "{} octets attendus pour un {}, {} trouvés",
+                            size_of::<$int>(),
+                            stringify!($int),
+                            bytes.len()
+                        )
+                    })?;
+                    Ok(<$int>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+int_codec!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+macro_rules! platform_int_codec {
+    ($($int:ty => $fixed:ty),*) => {
+        $(
+            impl Codec<$int> for IntCodec {
+                fn encode(&self, value: &$int) -> Vec<u8> {
+                    (*value as $fixed).to_le_bytes().to_vec()
+                }
+
+                fn decode(&self, bytes: &[u8]) -> Result<$int, String> {
+                    let value: $fixed = self.decode(bytes)?;
+                    <$int>::try_from(value).map_err(|_| {
+                        format!("{} ne tient pas dans un {}", value, stringify!($int))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+platform_int_codec!(usize => u64, isize => i64);
@@ -0,0 +1,85 @@
+//! Erreurs de la persistance d'un [`PersistentCache`](super::PersistentCache).
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// # Structure `BadLine`
+///
+/// Ligne d'un fichier de sauvegarde qui n'a pas pu être lue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadLine {
+    /// Numéro de la ligne dans le fichier, à partir de 1.
+    pub line: usize,
+    /// Contenu de la ligne.
+    pub text: String,
+    /// Raison de l'échec.
+    pub reason: String,
+}
+
+impl fmt::Display for BadLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ligne {} ({}) : {:?}", self.line, self.reason, self.text)
+    }
+}
+
+/// # Enum `StorageError`
+///
+/// Erreur retournée par les opérations de persistance.
+#[derive(Debug)]
+pub enum StorageError {
+    /// Erreur de lecture ou d'écriture du fichier.
+    Io(io::Error),
+    /// Ligne du fichier impossible à lire.
+    Parse(BadLine),
+    /// Version du format du fichier non prise en charge.
+    UnsupportedVersion {
+        /// Version trouvée dans le fichier.
+        found: u32,
+        /// Version la plus récente prise en charge.
+        supported: u32,
+    },
+    /// Transformation de la sauvegarde inconnue ou impossible à annuler.
+    Transform(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(err) => write!(f, "Erreur d'entrée/sortie : {}", err),
+            StorageError::Parse(bad_line) => write!(f, "Ligne invalide : {}", bad_line),
+            StorageError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "Version de fichier non prise en charge : {} (maximum {})",
+                found, supported
+            ),
+            StorageError::Transform(reason) => {
+                write!(f, "Transformation de la sauvegarde impossible : {}", reason)
+            }
+        }
+    }
+}
+
+impl Error for StorageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StorageError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StorageError {
+    fn from(err: io::Error) -> Self {
+        StorageError::Io(err)
+    }
+}
+
+impl From<StorageError> for io::Error {
+    fn from(err: StorageError) -> Self {
+        match err {
+            StorageError::Io(err) => err,
+            other => io::Error::new(io::ErrorKind::InvalidData, other),
+        }
+    }
+}
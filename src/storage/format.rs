@@ -0,0 +1,340 @@
+//! Format texte des fichiers de sauvegarde d'un [`PersistentCache`](super::PersistentCache).
+//!
+//! Un fichier commence par une ligne d'en-tête indiquant la version du format et
+//! les métadonnées du cache ([`CacheMeta`]), suivie d'une ligne `clé:valeur:méta`
+//! par élément, où `méta` contient les métadonnées de l'élément ([`EntryMeta`]). Dans la clé et la valeur, les
+//! caractères `\`, `:`, retour à la ligne et retour chariot sont échappés, ce qui
+//! permet de relire exactement n'importe quelle chaîne. Les octets qui ne forment
+//! pas de l'UTF-8 valide, produits par certains [`Codec`](super::codec::Codec),
+//! sont écrits sous la forme `\xHH`.
+//!
+//! ```text
+//! #cache_lru v3 capacity=3 policy=lru hits=10 misses=2 insertions=5 evictions=1
+//! http\://x:Valeur sur\nplusieurs lignes:inserted=1760781300123\naccesses=0\nweight=1
+//! ```
+//!
+//! Les fichiers de la version 2, sans métadonnées, et ceux de la version 1, sans
+//! en-tête ni échappement, restent lisibles.
+//!
+//! Le journal des opérations (fichier `.wal`) commence par son propre en-tête,
+//! suivi d'une ligne par opération, avec le même échappement :
+//!
+//! ```text
+//! #cache_lru log v2
+//! P clé:valeur:méta
+//! R clé
+//! T clé
+//! ```
+//!
+//! Avec des transformations, chaque lot d'opérations est écrit sur une ligne
+//! `B ...` décrite dans le module [`transform`](super::transform).
+//!
+//! [`CacheMeta`]: super::meta::CacheMeta
+//! [`EntryMeta`]: super::meta::EntryMeta
+
+use super::meta::CacheMeta;
+
+/// Version actuelle du format.
+pub const VERSION: u32 = 3;
+
+/// Début de la ligne d'en-tête, suivi du numéro de version.
+pub const HEADER_PREFIX: &str = "#cache_lru v";
+
+/// ## Fonction `header`
+///
+/// Retourne la ligne d'en-tête de la version actuelle, sans retour à la ligne.
+pub fn header() -> String {
+    format!("{}{}", HEADER_PREFIX, VERSION)
+}
+
+/// ## Fonction `parse_header`
+///
+/// Lit le numéro de version d'une ligne d'en-tête.
+///
+/// ### Retourne
+/// - `Some(u32)` : La version si la ligne est un en-tête.
+/// - `None` : Si la ligne n'est pas un en-tête (fichier de la version 1).
+pub fn parse_header(line: &str) -> Option<u32> {
+    line.strip_prefix(HEADER_PREFIX)?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// ## Fonction `header_with_meta`
+///
+/// Retourne la ligne d'en-tête suivie des métadonnées du cache, sans retour à la
+/// ligne.
+pub fn header_with_meta(meta: &CacheMeta) -> String {
+    format!("{} {}", header(), meta.encode())
+}
+
+/// ## Fonction `parse_header_meta`
+///
+/// Lit les métadonnées du cache d'une ligne d'en-tête.
+///
+/// ### Retourne
+/// - `Some(CacheMeta)` : Les métadonnées si l'en-tête en contient.
+/// - `None` : Sinon, pour les fichiers des versions 1 et 2.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::format::{header_with_meta, parse_header, parse_header_meta};
+/// # use cache_lru::storage::CacheMeta;
+/// let meta = CacheMeta { capacity: 3, policy: "lru".to_string(), stats: Default::default() };
+/// let line = header_with_meta(&meta);
+/// assert_eq!(parse_header(&line), Some(3));
+/// assert_eq!(parse_header_meta(&line), Some(meta));
+/// assert_eq!(parse_header_meta("#cache_lru v2"), None);
+/// ```
+pub fn parse_header_meta(line: &str) -> Option<CacheMeta> {
+    let (_, meta) = line.strip_prefix(HEADER_PREFIX)?.split_once(' ')?;
+    CacheMeta::decode(meta)
+}
+
+/// ## Fonction `escape`
+///
+/// Échappe les caractères `\`, `:`, `\n` et `\r` d'une suite d'octets. Les octets
+/// qui ne forment pas de l'UTF-8 valide sont écrits sous la forme `\xHH`.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::format::escape;
+/// assert_eq!(escape(b"http://x"), "http\\://x");
+/// assert_eq!(escape(&[b'a', 0xFF]), "a\\xff");
+/// ```
+pub fn escape(bytes: &[u8]) -> String {
+    let mut escaped = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                ':' => escaped.push_str("\\:"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                _ => escaped.push(c),
+            }
+        }
+        for byte in chunk.invalid() {
+            escaped.push_str(&format!("\\x{:02x}", byte));
+        }
+    }
+    escaped
+}
+
+/// ## Fonction `unescape`
+///
+/// Inverse de [`escape`].
+///
+/// ### Retourne
+/// - `Some(Vec<u8>)` : Les octets d'origine.
+/// - `None` : Si la chaîne contient une séquence d'échappement invalide.
+pub fn unescape(text: &str) -> Option<Vec<u8>> {
+    let mut unescaped = Vec::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buffer = [0; 4];
+            unescaped.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+        match chars.next()? {
+            '\\' => unescaped.push(b'\\'),
+            ':' => unescaped.push(b':'),
+            'n' => unescaped.push(b'\n'),
+            'r' => unescaped.push(b'\r'),
+            'x' => {
+                let high = chars.next()?.to_digit(16)?;
+                let low = chars.next()?.to_digit(16)?;
+                unescaped.push((high * 16 + low) as u8);
+            }
+            _ => return None,
+        }
+    }
+    Some(unescaped)
+}
+
+/// ## Fonction `encode_record`
+///
+/// Encode un élément sous la forme d'une ligne `clé:valeur` échappée, sans
+/// retour à la ligne.
+pub fn encode_record(key: &[u8], value: &[u8]) -> String {
+    format!("{}:{}", escape(key), escape(value))
+}
+
+/// ## Fonction `decode_record`
+///
+/// Décode une ligne produite par [`encode_record`], en la séparant sur le premier
+/// `:` non échappé.
+///
+/// ### Retourne
+/// - `Some((Vec<u8>, Vec<u8>))` : La clé et la valeur.
+/// - `None` : Si la ligne est mal formée.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::format::{decode_record, encode_record};
+/// let line = encode_record(b"http://x", b"a\nb");
+/// assert_eq!(
+///     decode_record(&line),
+///     Some((b"http://x".to_vec(), b"a\nb".to_vec()))
+/// );
+/// ```
+pub fn decode_record(line: &str) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ':' => return Some((unescape(&line[..i])?, unescape(&line[i + 1..])?)),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// ## Fonction `encode_record_with_meta`
+///
+/// Encode un élément et ses métadonnées sous la forme d'une ligne
+/// `clé:valeur:méta` échappée. Sans métadonnées, la ligne est celle de
+/// [`encode_record`].
+pub fn encode_record_with_meta(key: &[u8], value: &[u8], meta: &[u8]) -> String {
+    if meta.is_empty() {
+        encode_record(key, value)
+    } else {
+        format!("{}:{}", encode_record(key, value), escape(meta))
+    }
+}
+
+/// ## Fonction `decode_record_with_meta`
+///
+/// Décode une ligne produite par [`encode_record_with_meta`] ou par
+/// [`encode_record`], en la séparant sur les `:` non échappés.
+///
+/// ### Retourne
+/// - `Some((Vec<u8>, Vec<u8>, Vec<u8>))` : La clé, la valeur et les métadonnées,
+///   vides si la ligne n'en contient pas.
+/// - `None` : Si la ligne est mal formée.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::format::{decode_record_with_meta, encode_record_with_meta};
+/// let line = encode_record_with_meta(b"k", b"a:b", b"weight=2");
+/// assert_eq!(line, "k:a\\:b:weight=2");
+/// assert_eq!(
+///     decode_record_with_meta(&line),
+///     Some((b"k".to_vec(), b"a:b".to_vec(), b"weight=2".to_vec()))
+/// );
+/// assert_eq!(
+///     decode_record_with_meta("k:v"),
+///     Some((b"k".to_vec(), b"v".to_vec(), Vec::new()))
+/// );
+/// ```
+pub fn decode_record_with_meta(line: &str) -> Option<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    let mut fields = Vec::with_capacity(3);
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ':' => {
+                fields.push(&line[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    fields.push(&line[start..]);
+    match fields[..] {
+        [key, value] => Some((unescape(key)?, unescape(value)?, Vec::new())),
+        [key, value, meta] => Some((unescape(key)?, unescape(value)?, unescape(meta)?)),
+        _ => None,
+    }
+}
+
+/// ## Fonction `decode_legacy_record`
+///
+/// Décode une ligne d'un fichier de la version 1, séparée sur le premier `:`.
+pub fn decode_legacy_record(line: &str) -> Option<(Vec<u8>, Vec<u8>)> {
+    line.split_once(':')
+        .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+}
+
+/// Version actuelle du format du journal.
+pub const LOG_VERSION: u32 = 2;
+
+/// Début de la ligne d'en-tête du journal, suivi du numéro de version.
+pub const LOG_HEADER_PREFIX: &str = "#cache_lru log v";
+
+/// ## Fonction `log_header`
+///
+/// Retourne la ligne d'en-tête du journal, sans retour à la ligne.
+pub fn log_header() -> String {
+    format!("{}{}", LOG_HEADER_PREFIX, LOG_VERSION)
+}
+
+/// ## Fonction `parse_log_header`
+///
+/// Lit le numéro de version d'une ligne d'en-tête du journal.
+pub fn parse_log_header(line: &str) -> Option<u32> {
+    line.strip_prefix(LOG_HEADER_PREFIX)?
+        .trim_end()
+        .parse()
+        .ok()
+}
+
+/// # Enum `LogRecord`
+///
+/// Opération enregistrée dans le journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogRecord {
+    /// Ajout ou mise à jour d'un élément, avec ses métadonnées encodées (vides
+    /// dans les journaux de la version 1).
+    Put(Vec<u8>, Vec<u8>, Vec<u8>),
+    /// Retrait d'un élément.
+    Remove(Vec<u8>),
+    /// Utilisation d'un élément, qui devient le plus récent.
+    Touch(Vec<u8>),
+}
+
+/// ## Fonction `encode_log_record`
+///
+/// Encode une opération du journal sur une ligne, sans retour à la ligne.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::format::{decode_log_record, encode_log_record, LogRecord};
+/// let record = LogRecord::Put(b"http://x".to_vec(), b"a".to_vec(), b"weight=2".to_vec());
+/// assert_eq!(encode_log_record(&record), "P http\\://x:a:weight=2");
+/// assert_eq!(decode_log_record(&encode_log_record(&record)), Some(record));
+/// ```
+pub fn encode_log_record(record: &LogRecord) -> String {
+    match record {
+        LogRecord::Put(key, value, meta) => {
+            format!("P {}", encode_record_with_meta(key, value, meta))
+        }
+        LogRecord::Remove(key) => format!("R {}", escape(key)),
+        LogRecord::Touch(key) => format!("T {}", escape(key)),
+    }
+}
+
+/// ## Fonction `decode_log_record`
+///
+/// Décode une ligne produite par [`encode_log_record`].
+///
+/// ### Retourne
+/// - `Some(LogRecord)` : L'opération.
+/// - `None` : Si la ligne est mal formée.
+pub fn decode_log_record(line: &str) -> Option<LogRecord> {
+    let (kind, rest) = line.split_at_checked(2)?;
+    match kind {
+        "P " => {
+            decode_record_with_meta(rest).map(|(key, value, meta)| LogRecord::Put(key, value, meta))
+        }
+        "R " => unescape(rest).map(LogRecord::Remove),
+        "T " => unescape(rest).map(LogRecord::Touch),
+        _ => None,
+    }
+}
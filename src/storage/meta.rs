@@ -0,0 +1,244 @@
+//! Métadonnées enregistrées avec les éléments d'un [`PersistentCache`](super::PersistentCache).
+//!
+//! Chaque élément porte sa date d'insertion, son éventuelle date d'expiration, son
+//! nombre d'accès, son poids et ses tags ([`EntryMeta`]). Le cache lui-même porte sa
+//! capacité, le nom de sa politique d'éviction et ses statistiques cumulées
+//! ([`CacheMeta`]). Les dates sont enregistrées en millisecondes depuis le
+//! 1er janvier 1970.
+//!
+//! Les métadonnées d'un élément sont encodées sur plusieurs lignes `nom=valeur`,
+//! les tags étant échappés comme dans le module [`format`](super::format) :
+//!
+//! ```text
+//! inserted=1760781300123
+//! expires=1760781360123
+//! accesses=4
+//! weight=1
+//! tag=user\:42
+//! ```
+//!
+//! Celles du cache tiennent sur une ligne de champs `nom=valeur` séparés par des
+//! espaces : `capacity=3 policy=lru hits=10 misses=2 insertions=5 evictions=1`.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::format::{escape, unescape};
+use crate::stats::Stats;
+
+/// # Structure `EntryMeta`
+///
+/// Métadonnées d'un élément.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::EntryMeta;
+/// # use std::time::Duration;
+/// let meta = EntryMeta::new()
+///     .with_ttl(Duration::from_secs(60))
+///     .with_weight(3)
+///     .with_tags(&["user:42"]);
+/// assert_eq!(EntryMeta::decode(&meta.encode()), Some(meta));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryMeta {
+    /// Date d'insertion de l'élément.
+    pub inserted_at: SystemTime,
+    /// Date à partir de laquelle l'élément est expiré, s'il expire.
+    pub expires_at: Option<SystemTime>,
+    /// Nombre de lectures ayant trouvé l'élément.
+    pub access_count: u64,
+    /// Poids de l'élément, 1 par défaut.
+    pub weight: u64,
+    /// Tags de l'élément.
+    pub tags: Vec<String>,
+}
+
+impl Default for EntryMeta {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntryMeta {
+    /// ## Méthode `new`
+    ///
+    /// Métadonnées d'un élément inséré maintenant, sans expiration, de poids 1 et
+    /// sans tag. La date est arrondie à la milliseconde, comme une fois enregistrée.
+    pub fn new() -> Self {
+        Self {
+            inserted_at: from_millis(to_millis(SystemTime::now())),
+            expires_at: None,
+            access_count: 0,
+            weight: 1,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Fait expirer l'élément après une durée à partir de son insertion.
+    pub fn with_ttl(self, ttl: Duration) -> Self {
+        Self {
+            expires_at: Some(self.inserted_at + ttl),
+            ..self
+        }
+    }
+
+    /// Change le poids de l'élément.
+    pub fn with_weight(self, weight: u64) -> Self {
+        Self { weight, ..self }
+    }
+
+    /// Remplace les tags de l'élément.
+    pub fn with_tags(self, tags: &[&str]) -> Self {
+        Self {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..self
+        }
+    }
+
+    /// ## Méthode `is_expired`
+    ///
+    /// Indique si l'élément est expiré à une date donnée.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// ## Méthode `encode`
+    ///
+    /// Encode les métadonnées sous la forme décrite dans ce module.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut lines = vec![format!("inserted={}", to_millis(self.inserted_at))];
+        if let Some(expires_at) = self.expires_at {
+            lines.push(format!("expires={}", to_millis(expires_at)));
+        }
+        lines.push(format!("accesses={}", self.access_count));
+        lines.push(format!("weight={}", self.weight));
+        for tag in &self.tags {
+            lines.push(format!("tag={}", escape(tag.as_bytes())));
+        }
+        lines.join("\n").into_bytes()
+    }
+
+    /// ## Méthode `decode`
+    ///
+    /// Inverse de [`EntryMeta::encode`]. Les champs inconnus sont ignorés.
+    ///
+    /// ### Retourne
+    /// - `Some(EntryMeta)` : Les métadonnées.
+    /// - `None` : Si un champ connu est invalide.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut meta = Self {
+            inserted_at: UNIX_EPOCH,
+            ..Self::new()
+        };
+        for line in std::str::from_utf8(bytes).ok()?.lines() {
+            let (name, value) = line.split_once('=')?;
+            match name {
+                "inserted" => meta.inserted_at = from_millis(value.parse().ok()?),
+                "expires" => meta.expires_at = Some(from_millis(value.parse().ok()?)),
+                "accesses" => meta.access_count = value.parse().ok()?,
+                "weight" => meta.weight = value.parse().ok()?,
+                "tag" => meta.tags.push(String::from_utf8(unescape(value)?).ok()?),
+                _ => {}
+            }
+        }
+        Some(meta)
+    }
+}
+
+/// # Structure `CacheMeta`
+///
+/// Métadonnées d'un cache, enregistrées avec sa sauvegarde.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheMeta {
+    /// Capacité du cache au moment de la sauvegarde.
+    pub capacity: usize,
+    /// Nom de la politique d'éviction, `lru` pour un [`PersistentCache`](super::PersistentCache).
+    pub policy: String,
+    /// Statistiques cumulées au moment de la sauvegarde.
+    pub stats: Stats,
+}
+
+impl CacheMeta {
+    /// ## Méthode `encode`
+    ///
+    /// Encode les métadonnées sur une ligne, sous la forme décrite dans ce module.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::stats::Stats;
+    /// # use cache_lru::storage::CacheMeta;
+    /// let meta = CacheMeta {
+    ///     capacity: 3,
+    ///     policy: "lru".to_string(),
+    ///     stats: Stats { hits: 10, ..Stats::default() },
+    /// };
+    /// assert_eq!(
+    ///     meta.encode(),
+    ///     "capacity=3 policy=lru hits=10 misses=0 insertions=0 evictions=0"
+    /// );
+    /// assert_eq!(CacheMeta::decode(&meta.encode()), Some(meta));
+    /// ```
+    pub fn encode(&self) -> String {
+        format!(
+            "capacity={} policy={} hits={} misses={} insertions={} evictions={}",
+            self.capacity,
+            self.policy,
+            self.stats.hits,
+            self.stats.misses,
+            self.stats.insertions,
+            self.stats.evictions
+        )
+    }
+
+    /// ## Méthode `decode`
+    ///
+    /// Inverse de [`CacheMeta::encode`]. Les champs inconnus sont ignorés.
+    ///
+    /// ### Retourne
+    /// - `Some(CacheMeta)` : Les métadonnées.
+    /// - `None` : Si un champ est invalide ou si la capacité manque.
+    pub fn decode(text: &str) -> Option<Self> {
+        let mut capacity = None;
+        let mut policy = String::new();
+        let mut stats = Stats::default();
+        for field in text.split_whitespace() {
+            let (name, value) = field.split_once('=')?;
+            match name {
+                "capacity" => capacity = Some(value.parse().ok()?),
+                "policy" => policy = value.to_string(),
+                "hits" => stats.hits = value.parse().ok()?,
+                "misses" => stats.misses = value.parse().ok()?,
+                "insertions" => stats.insertions = value.parse().ok()?,
+                "evictions" => stats.evictions = value.parse().ok()?,
+                _ => {}
+            }
+        }
+        Some(Self {
+            capacity: capacity?,
+            policy,
+            stats,
+        })
+    }
+
+    // Métadonnées les plus récentes entre deux parties d'une sauvegarde : les
+    // statistiques, cumulées, ne font que croître.
+    pub(crate) fn latest(self, other: Self) -> Self {
+        let total = |meta: &Self| {
+            meta.stats.hits + meta.stats.misses + meta.stats.insertions + meta.stats.evictions
+        };
+        if total(&other) > total(&self) {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+fn to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+fn from_millis(millis: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis)
+}
@@ -0,0 +1,134 @@
+//! Migration des fichiers de sauvegarde et du journal vers la version actuelle de
+//! leur format.
+//!
+//! Un registre de migrations ([`Migrations`]) associe à chaque version du format
+//! une étape qui convertit une ligne de cette version en une ligne de la version
+//! suivante. Au chargement, chaque ligne d'un fichier ancien passe par toutes les
+//! étapes jusqu'à la version actuelle, puis est lue comme une ligne de cette
+//! version. Un fichier d'une version plus récente que la version actuelle, ou
+//! d'une version sans chemin de migration, est refusé.
+//!
+//! Les étapes du format texte des sauvegardes sont les suivantes :
+//! - v1 → v2 : la ligne `clé:valeur`, sans échappement, est séparée sur le premier
+//!   `:` puis échappée.
+//! - v2 → v3 : une ligne sans métadonnées reste valide.
+//!
+//! Celle du journal, v1 → v2, conserve les lignes : une opération sans
+//! métadonnées reste valide. Les fichiers binaires anciens sont relus directement
+//! par le module [`binary`](super::binary).
+
+use std::collections::BTreeMap;
+
+use super::format;
+use super::StorageError;
+
+/// Étape de migration : convertit une ligne d'une version en une ligne de la
+/// version suivante, ou retourne `None` si la ligne est mal formée.
+pub type Step = fn(&str) -> Option<String>;
+
+/// # Structure `Migrations`
+///
+/// Registre des étapes de migration d'un format vers sa version actuelle.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::migration::Migrations;
+/// let migrations = Migrations::snapshot();
+/// assert_eq!(
+///     migrations.upgrade_line(1, "url:http://x"),
+///     Some("url:http\\://x".to_string())
+/// );
+/// assert!(migrations.check(99).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Migrations {
+    current: u32,
+    steps: BTreeMap<u32, Step>,
+}
+
+impl Migrations {
+    /// ## Méthode `new`
+    ///
+    /// Crée un registre vide pour un format dont la version actuelle est `current`.
+    pub fn new(current: u32) -> Self {
+        Self {
+            current,
+            steps: BTreeMap::new(),
+        }
+    }
+
+    /// ## Méthode `snapshot`
+    ///
+    /// Registre des migrations du format texte des sauvegardes, décrites dans ce
+    /// module.
+    pub fn snapshot() -> Self {
+        let mut migrations = Self::new(format::VERSION);
+        migrations.register(1, |line| {
+            format::decode_legacy_record(line)
+                .map(|(key, value)| format::encode_record(&key, &value))
+        });
+        migrations.register(2, |line| Some(line.to_string()));
+        migrations
+    }
+
+    /// ## Méthode `log`
+    ///
+    /// Registre des migrations du format du journal, décrites dans ce module.
+    pub fn log() -> Self {
+        let mut migrations = Self::new(format::LOG_VERSION);
+        migrations.register(1, |line| Some(line.to_string()));
+        migrations
+    }
+
+    /// ## Méthode `register`
+    ///
+    /// Ajoute l'étape qui convertit une ligne de la version `from` en une ligne de
+    /// la version `from + 1`. Elle remplace l'étape déjà enregistrée pour cette
+    /// version, par exemple pour relire un ancien format propre à une application
+    /// avec [`PersistentCache::set_migrations`](super::PersistentCache::set_migrations).
+    pub fn register(&mut self, from: u32, step: Step) {
+        self.steps.insert(from, step);
+    }
+
+    /// ## Méthode `current`
+    ///
+    /// Retourne la version actuelle du format.
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// ## Méthode `check`
+    ///
+    /// Vérifie qu'un fichier d'une version donnée peut être migré vers la version
+    /// actuelle.
+    ///
+    /// ### Retourne
+    /// - `Ok(())` : Si chaque version jusqu'à la version actuelle a une étape.
+    /// - `Err(StorageError::UnsupportedVersion)` : Si la version est plus récente
+    ///   que la version actuelle, ou si une étape manque.
+    pub fn check(&self, version: u32) -> Result<(), StorageError> {
+        if version > self.current || (version..self.current).any(|v| !self.steps.contains_key(&v)) {
+            return Err(StorageError::UnsupportedVersion {
+                found: version,
+                supported: self.current,
+            });
+        }
+        Ok(())
+    }
+
+    /// ## Méthode `upgrade_line`
+    ///
+    /// Convertit une ligne d'une version donnée en une ligne de la version actuelle,
+    /// en appliquant les étapes dans l'ordre.
+    ///
+    /// ### Retourne
+    /// - `Some(String)` : La ligne dans la version actuelle.
+    /// - `None` : Si une étape échoue ou manque.
+    pub fn upgrade_line(&self, version: u32, line: &str) -> Option<String> {
+        let mut line = line.to_string();
+        for from in version..self.current {
+            line = self.steps.get(&from)?(&line)?;
+        }
+        Some(line)
+    }
+}
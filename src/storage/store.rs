@@ -0,0 +1,730 @@
+//! Stockage des fichiers d'un [`PersistentCache`](super::PersistentCache).
+//!
+//! Un stockage contient la sauvegarde du cache, répartie en une ou plusieurs
+//! parties, et un journal des opérations effectuées depuis. Le cache choisit la
+//! partie de chaque élément ; lors d'une compaction, seules les parties modifiées
+//! sont réécrites.
+//!
+//! ## Fonctionnalités principales
+//! - Définir son propre stockage avec le trait [`Store`].
+//! - Stocker le cache dans un fichier unique avec [`FileStore`].
+//! - Répartir le cache entre plusieurs fichiers d'un dossier avec [`DirStore`].
+//! - Stocker le cache en mémoire, pour les tests, avec [`MemoryStore`].
+//!
+//! Un stockage peut aussi conserver des archives de la sauvegarde, nommées par un
+//! numéro (`1` pour la plus récente, puis `2`, ...) ou par leur date de création
+//! (`@` suivi du nombre de millisecondes depuis le 1er janvier 1970), voir
+//! [`ArchiveNaming`].
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt::Debug;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::format;
+
+/// # Enum `ArchiveNaming`
+///
+/// Nom donné aux archives de la sauvegarde.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArchiveNaming {
+    /// La nouvelle archive s'appelle `1`, et les précédentes sont renumérotées.
+    #[default]
+    Numbered,
+    /// Chaque archive porte sa date de création, par exemple `@1760781300123`.
+    Timestamped,
+}
+
+/// # Trait `Store`
+///
+/// Ce trait représente l'endroit où un cache persistant est enregistré.
+pub trait Store: Debug + Send {
+    /// Nombre de parties entre lesquelles la sauvegarde est répartie (au moins 1).
+    fn shards(&self) -> usize {
+        1
+    }
+
+    /// Lit la sauvegarde d'une partie, ou `None` si elle n'existe pas.
+    fn read_snapshot(&self, shard: usize) -> io::Result<Option<Vec<u8>>>;
+
+    /// Remplace la sauvegarde d'une partie. En cas d'arrêt brutal, la partie doit
+    /// contenir soit l'ancienne, soit la nouvelle version.
+    fn write_snapshot(&mut self, shard: usize, contents: &[u8]) -> io::Result<()>;
+
+    /// Lit le journal, ou `None` s'il n'existe pas.
+    fn read_log(&self) -> io::Result<Option<Vec<u8>>>;
+
+    /// Ajoute des octets à la fin du journal. Ils doivent survivre à un arrêt brutal.
+    fn append_log(&mut self, bytes: &[u8]) -> io::Result<()>;
+
+    /// Vide le journal.
+    fn truncate_log(&mut self) -> io::Result<()>;
+
+    /// Supprime les restes d'une sauvegarde interrompue. Appelée à l'ouverture du cache.
+    fn recover(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Verrouille le stockage pour les autres processus : en écriture si `exclusive`
+    /// est vrai, en lecture sinon. Le verrou est consultatif : il ne protège que des
+    /// processus qui le demandent aussi.
+    fn lock(&mut self, _exclusive: bool) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Libère le verrou pris par [`Store::lock`].
+    fn unlock(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Retourne une valeur qui change à chaque modification du contenu, par ce
+    /// processus ou par un autre.
+    fn generation(&self) -> io::Result<u64> {
+        Ok(0)
+    }
+
+    /// Copie la sauvegarde actuelle dans une nouvelle archive, en ne conservant que
+    /// les `keep` archives les plus récentes de ce type de nom, et retourne son nom.
+    fn archive(&mut self, _keep: usize, _naming: ArchiveNaming) -> io::Result<String> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Archives non prises en charge",
+        ))
+    }
+
+    /// Liste les noms des archives : les archives numérotées de la plus récente à
+    /// la plus ancienne, puis les archives datées de la plus récente à la plus ancienne.
+    fn archives(&self) -> io::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Remplace la sauvegarde par une archive et vide le journal.
+    fn restore(&mut self, name: &str) -> io::Result<()> {
+        Err(archive_not_found(name))
+    }
+}
+
+/// # Structure `FileStore`
+///
+/// Stockage dans un fichier unique. Le journal est le même chemin suivi de `.wal`,
+/// et les sauvegardes passent par un fichier temporaire suivi de `.tmp`. Le verrou
+/// porte sur le même chemin suivi de `.lock`, et la génération est calculée à partir
+/// de la date de modification et de la taille des fichiers.
+#[derive(Debug)]
+pub struct FileStore {
+    path: PathBuf,
+    log: Option<File>,
+    lock: Option<File>,
+}
+
+impl FileStore {
+    /// ## Méthode `new`
+    ///
+    /// Crée un stockage dans le fichier indiqué, sans rien écrire.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::storage::FileStore;
+    /// let store = FileStore::new("cache.txt");
+    /// ```
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            log: None,
+            lock: None,
+        }
+    }
+
+    /// ## Méthode `path`
+    ///
+    /// Retourne le chemin du fichier de sauvegarde.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// ## Méthode `archive_path`
+    ///
+    /// Retourne le chemin d'une archive : le fichier de sauvegarde suivi d'un point
+    /// et du nom de l'archive, par exemple `cache.txt.1`.
+    pub fn archive_path(&self, name: &str) -> PathBuf {
+        with_suffix(&self.path, &format!(".{}", name))
+    }
+}
+
+impl Store for FileStore {
+    fn read_snapshot(&self, _shard: usize) -> io::Result<Option<Vec<u8>>> {
+        read_if_exists(&self.path)
+    }
+
+    fn write_snapshot(&mut self, _shard: usize, contents: &[u8]) -> io::Result<()> {
+        replace_file(&self.path, contents)
+    }
+
+    fn read_log(&self) -> io::Result<Option<Vec<u8>>> {
+        read_if_exists(&with_suffix(&self.path, ".wal"))
+    }
+
+    fn append_log(&mut self, bytes: &[u8]) -> io::Result<()> {
+        append_to(&mut self.log, &with_suffix(&self.path, ".wal"), bytes)
+    }
+
+    fn truncate_log(&mut self) -> io::Result<()> {
+        truncate(&mut self.log, &with_suffix(&self.path, ".wal"))
+    }
+
+    fn recover(&mut self) -> io::Result<()> {
+        remove_if_exists(&with_suffix(&self.path, ".tmp"))
+    }
+
+    fn lock(&mut self, exclusive: bool) -> io::Result<()> {
+        lock_file(&mut self.lock, &with_suffix(&self.path, ".lock"), exclusive)
+    }
+
+    fn unlock(&mut self) -> io::Result<()> {
+        unlock_file(&self.lock)
+    }
+
+    fn generation(&self) -> io::Result<u64> {
+        files_generation(&[self.path.clone(), with_suffix(&self.path, ".wal")])
+    }
+
+    fn archive(&mut self, keep: usize, naming: ArchiveNaming) -> io::Result<String> {
+        let snapshot = self.read_snapshot(0)?.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "Aucune sauvegarde à archiver")
+        })?;
+        let rotation = rotation(&self.archives()?, keep, naming);
+        for name in &rotation.removed {
+            remove_if_exists(&self.archive_path(name))?;
+        }
+        for (from, to) in &rotation.renamed {
+            fs::rename(self.archive_path(from), self.archive_path(to))?;
+        }
+        replace_file(&self.archive_path(&rotation.name), &snapshot)?;
+        Ok(rotation.name)
+    }
+
+    fn archives(&self) -> io::Result<Vec<String>> {
+        let Some(file_name) = self.path.file_name().and_then(|name| name.to_str()) else {
+            return Ok(Vec::new());
+        };
+        let prefix = format!("{}.", file_name);
+        let names = list_dir(parent_dir(&self.path))?
+            .into_iter()
+            .filter_map(|name| Some(name.strip_prefix(&prefix)?.to_string()));
+        Ok(sorted_archives(names))
+    }
+
+    fn restore(&mut self, name: &str) -> io::Result<()> {
+        let snapshot =
+            read_if_exists(&self.archive_path(name))?.ok_or_else(|| archive_not_found(name))?;
+        self.write_snapshot(0, &snapshot)?;
+        self.truncate_log()
+    }
+}
+
+/// # Structure `DirStore`
+///
+/// Stockage dans un dossier : la sauvegarde est répartie entre plusieurs fichiers
+/// (`shard-0`, `shard-1`, ...), à côté d'un journal commun (`log.wal`). Modifier
+/// un élément ne réécrit que le fichier de sa partie lors de la compaction.
+///
+/// L'ordre d'utilisation est conservé exactement à l'intérieur de chaque partie ;
+/// entre les parties, il est approché au chargement. Le verrou porte sur le fichier
+/// `lock` du dossier, et chaque archive est un sous-dossier `archive-` suivi de son nom.
+#[derive(Debug)]
+pub struct DirStore {
+    dir: PathBuf,
+    shards: usize,
+    log: Option<File>,
+    lock: Option<File>,
+}
+
+impl DirStore {
+    /// ## Méthode `new`
+    ///
+    /// Crée un stockage dans un dossier, créé à la première écriture.
+    ///
+    /// ### Arguments
+    /// - `dir` : Le dossier.
+    /// - `shards` : Le nombre de parties (au moins 1). Il ne doit pas changer entre
+    ///   deux ouvertures d'un même dossier.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::storage::DirStore;
+    /// let store = DirStore::new("cache", 16);
+    /// ```
+    pub fn new(dir: impl AsRef<Path>, shards: usize) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            shards: shards.max(1),
+            log: None,
+            lock: None,
+        }
+    }
+
+    fn shard_path(&self, shard: usize) -> PathBuf {
+        self.dir.join(format!("shard-{}", shard))
+    }
+
+    fn log_path(&self) -> PathBuf {
+        self.dir.join("log.wal")
+    }
+
+    fn archive_dir(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("archive-{}", name))
+    }
+}
+
+impl Store for DirStore {
+    fn shards(&self) -> usize {
+        self.shards
+    }
+
+    fn read_snapshot(&self, shard: usize) -> io::Result<Option<Vec<u8>>> {
+        read_if_exists(&self.shard_path(shard))
+    }
+
+    fn write_snapshot(&mut self, shard: usize, contents: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        replace_file(&self.shard_path(shard), contents)
+    }
+
+    fn read_log(&self) -> io::Result<Option<Vec<u8>>> {
+        read_if_exists(&self.log_path())
+    }
+
+    fn append_log(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if self.log.is_none() {
+            fs::create_dir_all(&self.dir)?;
+        }
+        let log_path = self.log_path();
+        append_to(&mut self.log, &log_path, bytes)
+    }
+
+    fn truncate_log(&mut self) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let log_path = self.log_path();
+        truncate(&mut self.log, &log_path)
+    }
+
+    fn recover(&mut self) -> io::Result<()> {
+        (0..self.shards)
+            .try_for_each(|shard| remove_if_exists(&with_suffix(&self.shard_path(shard), ".tmp")))
+    }
+
+    fn lock(&mut self, exclusive: bool) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let lock_path = self.dir.join("lock");
+        lock_file(&mut self.lock, &lock_path, exclusive)
+    }
+
+    fn unlock(&mut self) -> io::Result<()> {
+        unlock_file(&self.lock)
+    }
+
+    fn generation(&self) -> io::Result<u64> {
+        let mut paths: Vec<PathBuf> = (0..self.shards)
+            .map(|shard| self.shard_path(shard))
+            .collect();
+        paths.push(self.log_path());
+        files_generation(&paths)
+    }
+
+    // L'archive est écrite dans un dossier temporaire, renommé une fois complet.
+    fn archive(&mut self, keep: usize, naming: ArchiveNaming) -> io::Result<String> {
+        let snapshots = (0..self.shards)
+            .map(|shard| self.read_snapshot(shard))
+            .collect::<io::Result<Vec<_>>>()?;
+        if snapshots.iter().all(Option::is_none) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Aucune sauvegarde à archiver",
+            ));
+        }
+        let rotation = rotation(&self.archives()?, keep, naming);
+        let temp_dir = with_suffix(&self.archive_dir(&rotation.name), ".tmp");
+        remove_dir_if_exists(&temp_dir)?;
+        fs::create_dir(&temp_dir)?;
+        for (shard, snapshot) in snapshots.iter().enumerate() {
+            if let Some(snapshot) = snapshot {
+                replace_file(&temp_dir.join(format!("shard-{}", shard)), snapshot)?;
+            }
+        }
+        for name in &rotation.removed {
+            remove_dir_if_exists(&self.archive_dir(name))?;
+        }
+        for (from, to) in &rotation.renamed {
+            fs::rename(self.archive_dir(from), self.archive_dir(to))?;
+        }
+        remove_dir_if_exists(&self.archive_dir(&rotation.name))?;
+        fs::rename(&temp_dir, self.archive_dir(&rotation.name))?;
+        sync_parent_dir(&temp_dir)?;
+        Ok(rotation.name)
+    }
+
+    fn archives(&self) -> io::Result<Vec<String>> {
+        let names = list_dir(&self.dir)?
+            .into_iter()
+            .filter_map(|name| Some(name.strip_prefix("archive-")?.to_string()));
+        Ok(sorted_archives(names))
+    }
+
+    fn restore(&mut self, name: &str) -> io::Result<()> {
+        let archive_dir = self.archive_dir(name);
+        if !archive_dir.is_dir() {
+            return Err(archive_not_found(name));
+        }
+        for shard in 0..self.shards {
+            match read_if_exists(&archive_dir.join(format!("shard-{}", shard)))? {
+                Some(snapshot) => self.write_snapshot(shard, &snapshot)?,
+                None => remove_if_exists(&self.shard_path(shard))?,
+            }
+        }
+        self.truncate_log()
+    }
+}
+
+/// # Structure `MemoryStore`
+///
+/// Stockage en mémoire, utile pour les tests. Les clones d'un `MemoryStore`
+/// partagent le même contenu : un clone permet de rouvrir un cache sur les
+/// données d'un autre.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::{MemoryStore, PersistentCache, StringCodec};
+/// let store = MemoryStore::new();
+/// let mut cache: PersistentCache<String, String> =
+///     PersistentCache::with_store(3, store.clone(), StringCodec, StringCodec);
+/// cache.put("A".to_string(), "Valeur A".to_string()).unwrap();
+///
+/// let mut reopened: PersistentCache<String, String> =
+///     PersistentCache::open_with_store(3, store, StringCodec, StringCodec).unwrap();
+/// assert_eq!(reopened.get(&"A".to_string()), Some(&"Valeur A".to_string()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    contents: Arc<Mutex<MemoryContents>>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryContents {
+    snapshots: HashMap<usize, Vec<u8>>,
+    log: Option<Vec<u8>>,
+    // Nombre d'écritures, utilisé comme génération.
+    writes: u64,
+    archives: HashMap<String, HashMap<usize, Vec<u8>>>,
+}
+
+impl MemoryStore {
+    /// ## Méthode `new`
+    ///
+    /// Crée un stockage en mémoire vide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn contents(&self) -> std::sync::MutexGuard<'_, MemoryContents> {
+        self.contents.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Store for MemoryStore {
+    fn read_snapshot(&self, shard: usize) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.contents().snapshots.get(&shard).cloned())
+    }
+
+    fn write_snapshot(&mut self, shard: usize, contents: &[u8]) -> io::Result<()> {
+        let mut memory = self.contents();
+        memory.snapshots.insert(shard, contents.to_vec());
+        memory.writes += 1;
+        Ok(())
+    }
+
+    fn read_log(&self) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.contents().log.clone())
+    }
+
+    fn append_log(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let mut memory = self.contents();
+        memory
+            .log
+            .get_or_insert_with(Vec::new)
+            .extend_from_slice(bytes);
+        memory.writes += 1;
+        Ok(())
+    }
+
+    fn truncate_log(&mut self) -> io::Result<()> {
+        let mut memory = self.contents();
+        memory.log = Some(Vec::new());
+        memory.writes += 1;
+        Ok(())
+    }
+
+    fn generation(&self) -> io::Result<u64> {
+        Ok(self.contents().writes)
+    }
+
+    fn archive(&mut self, keep: usize, naming: ArchiveNaming) -> io::Result<String> {
+        let rotation = rotation(&self.archives()?, keep, naming);
+        let mut memory = self.contents();
+        if memory.snapshots.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Aucune sauvegarde à archiver",
+            ));
+        }
+        for name in &rotation.removed {
+            memory.archives.remove(name);
+        }
+        for (from, to) in &rotation.renamed {
+            if let Some(archive) = memory.archives.remove(from) {
+                memory.archives.insert(to.clone(), archive);
+            }
+        }
+        let snapshots = memory.snapshots.clone();
+        memory.archives.insert(rotation.name.clone(), snapshots);
+        Ok(rotation.name)
+    }
+
+    fn archives(&self) -> io::Result<Vec<String>> {
+        Ok(sorted_archives(self.contents().archives.keys().cloned()))
+    }
+
+    fn restore(&mut self, name: &str) -> io::Result<()> {
+        let mut memory = self.contents();
+        let snapshots = memory
+            .archives
+            .get(name)
+            .cloned()
+            .ok_or_else(|| archive_not_found(name))?;
+        memory.snapshots = snapshots;
+        memory.log = Some(Vec::new());
+        memory.writes += 1;
+        Ok(())
+    }
+}
+
+// Chemin suivi d'un suffixe, par exemple `cache.txt` et `.wal` pour `cache.txt.wal`.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+// Lit un fichier, ou retourne `None` s'il n'existe pas.
+fn read_if_exists(path: &Path) -> io::Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+// Remplace un fichier via un fichier temporaire du même dossier, renommé une fois
+// écrit sur disque.
+fn replace_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp_path = with_suffix(path, ".tmp");
+    let mut file = File::create(&temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&temp_path, path)?;
+    sync_parent_dir(path)
+}
+
+// Ajoute des octets à un journal, ouvert en ajout à la première écriture avec son
+// en-tête s'il est vide.
+fn append_to(log: &mut Option<File>, path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let file = match log {
+        Some(file) => file,
+        None => {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            if file.metadata()?.len() == 0 {
+                writeln!(file, "{}", format::log_header())?;
+            }
+            log.insert(file)
+        }
+    };
+    file.write_all(bytes)?;
+    file.sync_data()
+}
+
+fn truncate(log: &mut Option<File>, path: &Path) -> io::Result<()> {
+    let file = match log {
+        Some(file) => file,
+        None => log.insert(OpenOptions::new().create(true).append(true).open(path)?),
+    };
+    file.set_len(0)?;
+    writeln!(file, "{}", format::log_header())?;
+    file.sync_data()
+}
+
+// Archives à supprimer et à renommer pour en ajouter une, et nom de la nouvelle.
+struct Rotation {
+    removed: Vec<String>,
+    renamed: Vec<(String, String)>,
+    name: String,
+}
+
+// Prépare l'ajout d'une archive à celles existantes, listées par `sorted_archives`.
+// Les renommages sont dans l'ordre où les faire.
+fn rotation(existing: &[String], keep: usize, naming: ArchiveNaming) -> Rotation {
+    let keep = keep.max(1);
+    match naming {
+        ArchiveNaming::Numbered => {
+            let mut rotation = Rotation {
+                removed: Vec::new(),
+                renamed: Vec::new(),
+                name: "1".to_string(),
+            };
+            let numbers = existing.iter().filter_map(|name| name.parse::<u64>().ok());
+            for number in numbers.rev() {
+                if number as usize >= keep {
+                    rotation.removed.push(number.to_string());
+                } else {
+                    rotation
+                        .renamed
+                        .push((number.to_string(), (number + 1).to_string()));
+                }
+            }
+            rotation
+        }
+        ArchiveNaming::Timestamped => {
+            let millis = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis());
+            let name = format!("@{}", millis);
+            // Une archive créée dans la même milliseconde est remplacée.
+            let removed = existing
+                .iter()
+                .filter(|archive| archive.starts_with('@') && **archive != name)
+                .skip(keep - 1)
+                .cloned()
+                .collect();
+            Rotation {
+                removed,
+                renamed: Vec::new(),
+                name,
+            }
+        }
+    }
+}
+
+// Garde les noms d'archive valides et les trie : numérotées dans l'ordre croissant,
+// puis datées de la plus récente à la plus ancienne.
+fn sorted_archives(names: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut archives: Vec<_> = names
+        .into_iter()
+        .filter_map(|name| {
+            let order = match name.strip_prefix('@') {
+                Some(millis) => (1, u64::MAX - millis.parse::<u64>().ok()?),
+                None => (0, name.parse::<u64>().ok()?),
+            };
+            Some((order, name))
+        })
+        .collect();
+    archives.sort();
+    archives.into_iter().map(|(_, name)| name).collect()
+}
+
+fn archive_not_found(name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("Archive introuvable : {}", name),
+    )
+}
+
+// Noms des entrées d'un dossier, vide s'il n'existe pas.
+fn list_dir(dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        if let Ok(name) = entry?.file_name().into_string() {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+fn remove_dir_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+// Dossier contenant un fichier, `.` pour un chemin relatif sans dossier.
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+// Verrouille un fichier de verrou, ouvert à la première utilisation.
+fn lock_file(lock: &mut Option<File>, path: &Path, exclusive: bool) -> io::Result<()> {
+    let file = match lock {
+        Some(file) => file,
+        None => lock.insert(
+            OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(path)?,
+        ),
+    };
+    if exclusive {
+        file.lock()
+    } else {
+        file.lock_shared()
+    }
+}
+
+fn unlock_file(lock: &Option<File>) -> io::Result<()> {
+    match lock {
+        Some(file) => file.unlock(),
+        None => Ok(()),
+    }
+}
+
+// Génération calculée à partir de la taille et de la date de modification de
+// fichiers, absents compris.
+fn files_generation(paths: &[PathBuf]) -> io::Result<u64> {
+    let mut hasher = DefaultHasher::new();
+    for path in paths {
+        match fs::metadata(path) {
+            Ok(metadata) => (metadata.len(), metadata.modified()?).hash(&mut hasher),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None::<u64>.hash(&mut hasher),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(hasher.finish())
+}
+
+// Force l'écriture sur disque du dossier contenant un fichier, pour que son
+// renommage survive à un arrêt brutal.
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(parent_dir(path))?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
@@ -0,0 +1,343 @@
+//! Transformations des fichiers de sauvegarde d'un [`PersistentCache`](super::PersistentCache),
+//! appliquées entre l'écriture des éléments et le stockage.
+//!
+//! ## Fonctionnalités principales
+//! - Définir sa propre transformation, par exemple un chiffrement, avec le trait
+//!   [`Transform`].
+//! - Compresser les sauvegardes avec [`Lz`].
+//! - Enchaîner plusieurs transformations ([`PersistentCache::add_transform`](super::PersistentCache::add_transform)).
+//!
+//! Une sauvegarde transformée commence par le nombre magique [`MAGIC`], suivi de la
+//! liste des transformations appliquées, dans l'ordre, puis du contenu transformé.
+//! Les entiers sont en petit-boutiste :
+//!
+//! ```text
+//! CLRT | longueur (u32) | noms séparés par des virgules | contenu transformé
+//! ```
+//!
+//! À la lecture, les transformations sont annulées dans l'ordre inverse. Une
+//! sauvegarde sans ce nombre magique est relue telle quelle.
+//!
+//! Le journal reste un fichier texte d'une ligne par écriture, pour qu'une écriture
+//! interrompue n'affecte que sa dernière ligne. Chaque lot d'opérations écrit
+//! ensemble est transformé de la même façon, puis écrit en base64 (alphabet
+//! standard, avec `=` final) sur une ligne commençant par [`BATCH_PREFIX`]
+//! ([`apply_batch`]).
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use super::StorageError;
+
+/// Nombre magique au début d'une sauvegarde transformée.
+pub const MAGIC: &[u8; 4] = b"CLRT";
+
+/// # Trait `Transform`
+///
+/// Ce trait transforme le contenu d'une sauvegarde, et inversement. Les octets
+/// produits par [`Transform::encode`] doivent être rendus à l'identique par
+/// [`Transform::decode`].
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::transform::Transform;
+/// #[derive(Debug)]
+/// struct Xor(u8);
+///
+/// impl Transform for Xor {
+///     fn name(&self) -> &str {
+///         "xor"
+///     }
+///
+///     fn encode(&self, bytes: &[u8]) -> Vec<u8> {
+///         bytes.iter().map(|byte| byte ^ self.0).collect()
+///     }
+///
+///     fn decode(&self, bytes: &[u8]) -> Result<Vec<u8>, String> {
+///         Ok(self.encode(bytes))
+///     }
+/// }
+/// ```
+pub trait Transform: Debug + Send {
+    /// Nom enregistré dans l'en-tête de la sauvegarde, sans espace ni virgule.
+    fn name(&self) -> &str;
+
+    /// Transforme le contenu d'une sauvegarde.
+    fn encode(&self, bytes: &[u8]) -> Vec<u8>;
+
+    /// Rend le contenu d'origine, ou retourne la raison de l'échec.
+    fn decode(&self, bytes: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+/// ## Fonction `apply`
+///
+/// Applique des transformations dans l'ordre et ajoute l'en-tête qui les liste.
+/// Sans transformation, le contenu est retourné tel quel.
+pub fn apply(transforms: &[Box<dyn Transform>], bytes: Vec<u8>) -> Vec<u8> {
+    if transforms.is_empty() {
+        return bytes;
+    }
+    let names: Vec<&str> = transforms
+        .iter()
+        .map(|transform| transform.name())
+        .collect();
+    let names = names.join(",");
+    let body = transforms
+        .iter()
+        .fold(bytes, |bytes, transform| transform.encode(&bytes));
+    let mut transformed = MAGIC.to_vec();
+    transformed.extend_from_slice(&(names.len() as u32).to_le_bytes());
+    transformed.extend_from_slice(names.as_bytes());
+    transformed.extend_from_slice(&body);
+    transformed
+}
+
+/// ## Fonction `revert`
+///
+/// Annule les transformations listées dans l'en-tête d'une sauvegarde, dans l'ordre
+/// inverse. Chacune est cherchée par son nom parmi `transforms`.
+///
+/// ### Retourne
+/// - `Ok(Vec<u8>)` : Le contenu d'origine, ou le contenu tel quel s'il n'est pas
+///   transformé.
+/// - `Err(StorageError::Transform)` : Si l'en-tête est incomplet, si une
+///   transformation est inconnue ou si elle échoue.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::transform::{apply, revert, Lz, Transform};
+/// let transforms: Vec<Box<dyn Transform>> = vec![Box::new(Lz)];
+/// let content = b"#cache_lru v3\nA:aaaaaaaaaaaaaaaaaaaaaaaa\n".to_vec();
+/// let transformed = apply(&transforms, content.clone());
+/// assert!(transformed.starts_with(b"CLRT\x02\0\0\0lz"));
+/// assert_eq!(revert(&transforms, &transformed).unwrap(), content);
+/// assert!(revert(&[], &transformed).is_err());
+/// ```
+pub fn revert(transforms: &[Box<dyn Transform>], bytes: &[u8]) -> Result<Vec<u8>, StorageError> {
+    let Some(rest) = bytes.strip_prefix(MAGIC.as_slice()) else {
+        return Ok(bytes.to_vec());
+    };
+    let invalid = || StorageError::Transform("en-tête incomplet".to_string());
+    let len = rest
+        .get(..4)
+        .and_then(|len| len.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or_else(invalid)? as usize;
+    let names = rest.get(4..4 + len).ok_or_else(invalid)?;
+    let names = std::str::from_utf8(names).map_err(|_| invalid())?;
+
+    let known: HashMap<&str, &dyn Transform> = transforms
+        .iter()
+        .map(|transform| (transform.name(), transform.as_ref()))
+        .collect();
+    let mut content = rest[4 + len..].to_vec();
+    for name in names.split(',').rev() {
+        let transform = known
+            .get(name)
+            .ok_or_else(|| StorageError::Transform(format!("{} inconnue", name)))?;
+        content = transform
+            .decode(&content)
+            .map_err(|reason| StorageError::Transform(format!("{} : {}", name, reason)))?;
+    }
+    Ok(content)
+}
+
+/// Début d'une ligne du journal contenant un lot d'opérations transformé.
+pub const BATCH_PREFIX: &str = "B ";
+
+/// ## Fonction `apply_batch`
+///
+/// Transforme un lot de lignes du journal et l'écrit sur une seule ligne, sans
+/// retour à la ligne. Sans transformation, le lot est retourné tel quel.
+///
+/// ### Exemple
+/// ```rust
+/// # use cache_lru::storage::transform::{apply_batch, revert_batch, Lz, Transform};
+/// let transforms: Vec<Box<dyn Transform>> = vec![Box::new(Lz)];
+/// let batch = "P A:1:weight=1\nR B\n";
+/// let line = apply_batch(&transforms, batch);
+/// assert!(line.starts_with("B Q0xSV"));
+/// assert_eq!(revert_batch(&transforms, &line).unwrap(), Some(batch.to_string()));
+/// assert_eq!(revert_batch(&transforms, "R B").unwrap(), None);
+/// ```
+pub fn apply_batch(transforms: &[Box<dyn Transform>], batch: &str) -> String {
+    if transforms.is_empty() {
+        return batch.to_string();
+    }
+    let transformed = apply(transforms, batch.as_bytes().to_vec());
+    let mut line = String::with_capacity(BATCH_PREFIX.len() + transformed.len().div_ceil(3) * 4);
+    line.push_str(BATCH_PREFIX);
+    encode_base64(&transformed, &mut line);
+    line
+}
+
+/// ## Fonction `revert_batch`
+///
+/// Inverse de [`apply_batch`] pour une ligne du journal.
+///
+/// ### Retourne
+/// - `Ok(Some(String))` : Les lignes du lot, si la ligne contient un lot transformé.
+/// - `Ok(None)` : Si la ligne est une opération non transformée.
+/// - `Err(StorageError::Transform)` : Si le lot est mal formé ou si une
+///   transformation échoue.
+pub fn revert_batch(
+    transforms: &[Box<dyn Transform>],
+    line: &str,
+) -> Result<Option<String>, StorageError> {
+    let Some(encoded) = line.strip_prefix(BATCH_PREFIX) else {
+        return Ok(None);
+    };
+    let invalid = || StorageError::Transform("lot du journal mal formé".to_string());
+    let bytes = decode_base64(encoded).ok_or_else(invalid)?;
+    if !bytes.starts_with(MAGIC) {
+        return Err(invalid());
+    }
+    let batch = revert(transforms, &bytes)?;
+    String::from_utf8(batch).map(Some).map_err(|_| invalid())
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Ajoute des octets encodés en base64 à la fin d'une chaîne.
+fn encode_base64(bytes: &[u8], encoded: &mut String) {
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(group >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+}
+
+// Inverse de `encode_base64`, ou `None` si le texte n'est pas du base64 valide.
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let encoded = encoded.as_bytes();
+    if !encoded.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(encoded.len() / 4 * 3);
+    for chunk in encoded.chunks(4) {
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 {
+            return None;
+        }
+        let mut group = 0u32;
+        for (i, &c) in chunk[..4 - padding].iter().enumerate() {
+            let value = BASE64.iter().position(|&b| b == c)? as u32;
+            group |= value << (18 - 6 * i);
+        }
+        bytes.extend_from_slice(&group.to_be_bytes()[1..4 - padding]);
+    }
+    Some(bytes)
+}
+
+/// # Structure `Lz`
+///
+/// Compression de type LZ77, sans dépendance. Le contenu est une suite de blocs :
+/// - un octet `0xxxxxxx`, suivi de `x + 1` octets copiés tels quels ;
+/// - un octet `1xxxxxxx`, suivi d'une distance (u16), qui recopie `x + 4` octets
+///   situés à cette distance en arrière dans le contenu déjà produit.
+///
+/// Efficace sur les sauvegardes au format texte, dont les lignes se ressemblent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Lz;
+
+// Longueurs minimale et maximale d'une recopie, et nombre maximal d'octets d'un
+// bloc copié tel quel.
+const MIN_MATCH: usize = 4;
+const MAX_MATCH: usize = MIN_MATCH + 0x7F;
+const MAX_LITERALS: usize = 0x80;
+const HASH_BITS: u32 = 12;
+
+impl Transform for Lz {
+    fn name(&self) -> &str {
+        "lz"
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(bytes.len() / 2 + 16);
+        // Dernière position vue pour chaque empreinte de 4 octets.
+        let mut table = vec![usize::MAX; 1 << HASH_BITS];
+        let mut literals = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let candidate = match bytes.get(i..i + MIN_MATCH) {
+                Some(window) => {
+                    let slot = &mut table[hash(window)];
+                    let candidate = *slot;
+                    *slot = i;
+                    candidate
+                }
+                None => usize::MAX,
+            };
+            let len = if candidate != usize::MAX && i - candidate <= u16::MAX as usize {
+                bytes[candidate..]
+                    .iter()
+                    .zip(&bytes[i..])
+                    .take(MAX_MATCH)
+                    .take_while(|(a, b)| a == b)
+                    .count()
+            } else {
+                0
+            };
+            if len >= MIN_MATCH {
+                flush_literals(&mut encoded, &bytes[i - literals..i]);
+                literals = 0;
+                encoded.push(0x80 | (len - MIN_MATCH) as u8);
+                encoded.extend_from_slice(&((i - candidate) as u16).to_le_bytes());
+                i += len;
+            } else {
+                literals += 1;
+                i += 1;
+            }
+        }
+        flush_literals(&mut encoded, &bytes[bytes.len() - literals..]);
+        encoded
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let mut decoded = Vec::with_capacity(bytes.len() * 2);
+        let mut i = 0;
+        while let Some(&token) = bytes.get(i) {
+            i += 1;
+            if token & 0x80 == 0 {
+                let len = token as usize + 1;
+                let literals = bytes.get(i..i + len).ok_or("bloc tronqué")?;
+                decoded.extend_from_slice(literals);
+                i += len;
+            } else {
+                let len = (token & 0x7F) as usize + MIN_MATCH;
+                let distance = bytes.get(i..i + 2).ok_or("recopie tronquée")?;
+                let distance = u16::from_le_bytes([distance[0], distance[1]]) as usize;
+                i += 2;
+                let start = decoded
+                    .len()
+                    .checked_sub(distance)
+                    .filter(|_| distance > 0)
+                    .ok_or("distance invalide")?;
+                // La recopie peut chevaucher ce qu'elle produit : octet par octet.
+                for offset in 0..len {
+                    decoded.push(decoded[start + offset]);
+                }
+            }
+        }
+        Ok(decoded)
+    }
+}
+
+fn hash(window: &[u8]) -> usize {
+    let value = u32::from_le_bytes([window[0], window[1], window[2], window[3]]);
+    (value.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}
+
+fn flush_literals(encoded: &mut Vec<u8>, literals: &[u8]) {
+    for chunk in literals.chunks(MAX_LITERALS) {
+        encoded.push((chunk.len() - 1) as u8);
+        encoded.extend_from_slice(chunk);
+    }
+}
@@ -0,0 +1,158 @@
+//! Écriture dans le [`Store`] d'un [`PersistentCache`](super::PersistentCache), dans
+//! le thread appelant ou dans un thread dédié.
+
+use std::io;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+
+use super::Store;
+
+// Stockage partagé entre le cache et son thread d'écriture.
+pub(super) type SharedStore = Arc<Mutex<Box<dyn Store>>>;
+
+pub(super) fn lock(store: &SharedStore) -> MutexGuard<'_, Box<dyn Store>> {
+    store.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Remplace les parties indiquées de la sauvegarde, puis vide le journal.
+fn snapshot(store: &mut dyn Store, shards: &[(usize, Vec<u8>)]) -> io::Result<()> {
+    for (shard, contents) in shards {
+        store.write_snapshot(*shard, contents)?;
+    }
+    store.truncate_log()
+}
+
+// Destination des écritures : le stockage lui-même, ou le thread qui y écrit.
+#[derive(Debug)]
+pub(super) enum Sink {
+    Inline,
+    Background(Background),
+}
+
+impl Sink {
+    pub(super) fn append(&mut self, store: &SharedStore, lines: Vec<u8>) -> io::Result<()> {
+        match self {
+            Sink::Inline => lock(store).append_log(&lines),
+            Sink::Background(background) => background.send(Message::Append(lines)),
+        }
+    }
+
+    pub(super) fn snapshot(
+        &mut self,
+        store: &SharedStore,
+        shards: Vec<(usize, Vec<u8>)>,
+    ) -> io::Result<()> {
+        match self {
+            Sink::Inline => snapshot(lock(store).as_mut(), &shards),
+            Sink::Background(background) => background.send(Message::Snapshot(shards)),
+        }
+    }
+
+    // Attend que toutes les écritures envoyées soient terminées.
+    pub(super) fn sync(&mut self) -> io::Result<()> {
+        match self {
+            Sink::Inline => Ok(()),
+            Sink::Background(background) => background.sync(),
+        }
+    }
+
+    // Confie les écritures à un nouveau thread, après avoir attendu l'ancien.
+    pub(super) fn spawn(&mut self, store: &SharedStore, queue_capacity: usize) -> io::Result<()> {
+        *self = Sink::Inline;
+        *self = Sink::Background(Background::spawn(Arc::clone(store), queue_capacity)?);
+        Ok(())
+    }
+}
+
+enum Message {
+    Append(Vec<u8>),
+    Snapshot(Vec<(usize, Vec<u8>)>),
+    Sync(mpsc::Sender<io::Result<()>>),
+}
+
+// Thread d'écriture. À sa destruction, les écritures en attente sont terminées.
+#[derive(Debug)]
+pub(super) struct Background {
+    sender: Option<SyncSender<Message>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Background {
+    fn spawn(store: SharedStore, queue_capacity: usize) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::sync_channel(queue_capacity);
+        let handle = thread::Builder::new()
+            .name("cache_lru-writer".to_string())
+            .spawn(move || run(store, receiver))?;
+        Ok(Self {
+            sender: Some(sender),
+            handle: Some(handle),
+        })
+    }
+
+    // Envoie un message, en attendant si la file est pleine.
+    fn send(&self, message: Message) -> io::Result<()> {
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(message).ok())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "Thread d'écriture arrêté"))
+    }
+
+    fn sync(&self) -> io::Result<()> {
+        let (reply, response) = mpsc::channel();
+        self.send(Message::Sync(reply))?;
+        response.recv().unwrap_or_else(|_| {
+            Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "Thread d'écriture arrêté",
+            ))
+        })
+    }
+}
+
+impl Drop for Background {
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// Boucle du thread d'écriture. Les lignes reçues ensemble sont écrites en une fois.
+// La première erreur est conservée et retournée à la synchronisation suivante.
+fn run(store: SharedStore, receiver: Receiver<Message>) {
+    let mut error = None;
+    let mut lines = Vec::new();
+    while let Ok(message) = receiver.recv() {
+        for message in std::iter::once(message).chain(receiver.try_iter()) {
+            match message {
+                Message::Append(bytes) => lines.extend(bytes),
+                Message::Snapshot(shards) => {
+                    keep_first(&mut error, write_lines(&store, &mut lines));
+                    keep_first(&mut error, snapshot(lock(&store).as_mut(), &shards));
+                }
+                Message::Sync(reply) => {
+                    keep_first(&mut error, write_lines(&store, &mut lines));
+                    let _ = reply.send(error.take().map_or(Ok(()), Err));
+                }
+            }
+        }
+        keep_first(&mut error, write_lines(&store, &mut lines));
+    }
+}
+
+fn write_lines(store: &SharedStore, lines: &mut Vec<u8>) -> io::Result<()> {
+    if lines.is_empty() {
+        return Ok(());
+    }
+    let result = lock(store).append_log(lines);
+    lines.clear();
+    result
+}
+
+fn keep_first(error: &mut Option<io::Error>, result: io::Result<()>) {
+    if let Err(err) = result {
+        error.get_or_insert(err);
+    }
+}
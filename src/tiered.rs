@@ -0,0 +1,219 @@
+//! Module implémentant un cache à deux niveaux.
+//!
+//! Un petit [`Cache`] en mémoire (L1) est placé devant un [`PersistentCache`] plus
+//! grand, stocké sur disque (L2). Les lectures essaient L1 puis L2, et un élément
+//! trouvé dans L2 est promu dans L1. Les éléments évincés de L1 sont rétrogradés
+//! dans L2 au lieu d'être perdus.
+//!
+//! ## Fonctionnalités principales
+//! - Ajouter et récupérer des éléments avec [`TieredCache::put`] et [`TieredCache::get`].
+//! - Choisir une politique inclusive ou exclusive avec [`TierPolicy`].
+//! - Consulter les statistiques de chaque niveau avec [`TieredCache::stats`].
+
+use std::hash::Hash;
+
+use crate::cache_lru::Cache;
+use crate::stats::Stats;
+use crate::storage::{Codec, PersistentCache, StorageError, StringCodec};
+
+/// # Enum `TierPolicy`
+///
+/// Répartition des éléments entre les deux niveaux.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TierPolicy {
+    /// Tout élément de L1 est aussi présent dans L2 : un élément évincé de L2 est
+    /// aussi retiré de L1, et un élément évincé de L1 n'est pas réécrit dans L2.
+    Inclusive,
+    /// Un élément est présent dans un seul des deux niveaux.
+    Exclusive,
+}
+
+/// # Structure `TierStats`
+///
+/// Statistiques de chaque niveau d'un [`TieredCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TierStats {
+    /// Statistiques du cache en mémoire.
+    pub l1: Stats,
+    /// Statistiques du cache persistant.
+    pub l2: Stats,
+}
+
+/// # Structure `TieredCache`
+///
+/// Cette structure représente un cache à deux niveaux : un [`Cache`] en mémoire
+/// devant un [`PersistentCache`].
+///
+/// ## Types génériques
+/// - `K` : Type des clés (doit être `Eq`, `Hash`, et `Clone`).
+/// - `V` : Type des valeurs (doit être `Clone`).
+/// - `KC`, `VC` : Conversion des clés et des valeurs du cache persistant.
+#[derive(Debug)]
+pub struct TieredCache<K, V, KC = StringCodec, VC = StringCodec> {
+    l1: Cache<K, V>,
+    l2: PersistentCache<K, V, KC, VC>,
+    policy: TierPolicy,
+    stats: TierStats,
+}
+
+impl<K, V, KC, VC> TieredCache<K, V, KC, VC>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    KC: Codec<K>,
+    VC: Codec<V>,
+{
+    /// ## Méthode `new`
+    ///
+    /// Crée un cache à deux niveaux.
+    ///
+    /// ### Arguments
+    /// - `l1_capacity` : Capacité du cache en mémoire.
+    /// - `l2` : Le cache persistant utilisé comme second niveau.
+    /// - `policy` : La répartition des éléments entre les niveaux.
+    ///
+    /// ### Exemple
+    /// ```rust
+    /// # use cache_lru::storage::PersistentCache;
+    /// # use cache_lru::tiered::{TieredCache, TierPolicy};
+    /// let l2: PersistentCache<String, String> = PersistentCache::new(1000, "cache.txt");
+    /// let cache = TieredCache::new(10, l2, TierPolicy::Inclusive);
+    /// ```
+    pub fn new(l1_capacity: usize, l2: PersistentCache<K, V, KC, VC>, policy: TierPolicy) -> Self {
+        Self {
+            l1: Cache::new(l1_capacity),
+            l2,
+            policy,
+            stats: TierStats::default(),
+        }
+    }
+
+    /// ## Méthode `put`
+    ///
+    /// Ajoute une clé et une valeur dans L1. Avec la politique inclusive, la valeur
+    /// est aussi écrite dans L2 ; avec la politique exclusive, une éventuelle copie
+    /// dans L2 est retirée. L'élément évincé de L1 est rétrogradé dans L2.
+    ///
+    /// ### Arguments
+    /// - `key` : La clé associée à la valeur.
+    /// - `value` : La valeur à stocker.
+    ///
+    /// ### Retourne
+    /// - `Ok(())` : Si l'ajout réussit.
+    /// - `Err(StorageError)` : Si l'écriture dans L2 échoue.
+    pub fn put(&mut self, key: K, value: V) -> Result<(), StorageError> {
+        match self.policy {
+            TierPolicy::Inclusive => self.put_l2(key.clone(), value.clone())?,
+            TierPolicy::Exclusive => {
+                self.l2.remove(&key)?;
+            }
+        }
+        self.put_l1(key, value)
+    }
+
+    /// ## Méthode `get`
+    ///
+    /// Récupère une valeur dans L1, puis dans L2. Un élément trouvé dans L2 est
+    /// promu dans L1 (et retiré de L2 avec la politique exclusive).
+    ///
+    /// ### Retourne
+    /// - `Ok(Some(&V))` : La valeur liée à la clé si elle est présente dans un niveau.
+    /// - `Ok(None)` : Si la clé n'est présente dans aucun niveau.
+    /// - `Err(StorageError)` : Si l'écriture dans L2 due à la promotion échoue.
+    pub fn get(&mut self, key: &K) -> Result<Option<&V>, StorageError> {
+        if self.l1.contains(key) {
+            self.stats.l1.hits += 1;
+            return Ok(self.l1.get(key));
+        }
+        self.stats.l1.misses += 1;
+
+        match self.l2.get(key).cloned() {
+            Some(value) => {
+                self.stats.l2.hits += 1;
+                // La valeur n'est retirée de L2 qu'une fois dans L1 : un échec
+                // d'écriture ne la fait disparaître d'aucun niveau.
+                self.put_l1(key.clone(), value.clone())?;
+                if self.policy == TierPolicy::Exclusive {
+                    if let Err(err) = self.l2.remove(key) {
+                        // Le retrait est effectif en mémoire même si son
+                        // enregistrement échoue : la valeur est remise dans L2.
+                        let _ = self.l2.put(key.clone(), value);
+                        return Err(err);
+                    }
+                }
+                Ok(self.l1.get(key))
+            }
+            None => {
+                self.stats.l2.misses += 1;
+                Ok(None)
+            }
+        }
+    }
+
+    /// ## Méthode `remove`
+    ///
+    /// Retire une clé des deux niveaux.
+    ///
+    /// ### Retourne
+    /// - `Ok(Some(V))` : La valeur retirée.
+    /// - `Ok(None)` : Si la clé n'est présente dans aucun niveau.
+    /// - `Err(StorageError)` : Si l'écriture dans L2 échoue.
+    pub fn remove(&mut self, key: &K) -> Result<Option<V>, StorageError> {
+        let from_l1 = self.l1.remove(key);
+        let from_l2 = self.l2.remove(key)?;
+        Ok(from_l1.or(from_l2))
+    }
+
+    /// ## Méthode `stats`
+    ///
+    /// Retourne les statistiques de chaque niveau.
+    pub fn stats(&self) -> TierStats {
+        self.stats
+    }
+
+    /// ## Méthode `l1`
+    ///
+    /// Donne accès au cache en mémoire.
+    pub fn l1(&self) -> &Cache<K, V> {
+        &self.l1
+    }
+
+    /// ## Méthode `l2`
+    ///
+    /// Donne accès au cache persistant.
+    pub fn l2(&self) -> &PersistentCache<K, V, KC, VC> {
+        &self.l2
+    }
+
+    // Ajoute un élément dans L1. Avec la politique exclusive, l'élément qui va être
+    // évincé est d'abord rétrogradé dans L2 : si l'écriture échoue, il reste dans L1
+    // et le nouvel élément n'est pas ajouté. Avec la politique inclusive, il est
+    // déjà dans L2.
+    fn put_l1(&mut self, key: K, value: V) -> Result<(), StorageError> {
+        let full = !self.l1.contains(&key) && self.l1.len() >= self.l1.capacity();
+        if full && self.policy == TierPolicy::Exclusive {
+            if let Some((old_key, old_value)) = self.l1.peek_lru() {
+                let (old_key, old_value) = (old_key.clone(), old_value.clone());
+                self.put_l2(old_key, old_value)?;
+            }
+        }
+        self.stats.l1.insertions += 1;
+        if self.l1.push(key, value).is_some() {
+            self.stats.l1.evictions += 1;
+        }
+        Ok(())
+    }
+
+    // Ajoute un élément dans L2. Avec la politique inclusive, l'élément évincé de L2
+    // est aussi retiré de L1.
+    fn put_l2(&mut self, key: K, value: V) -> Result<(), StorageError> {
+        self.stats.l2.insertions += 1;
+        if let Some((old_key, _)) = self.l2.push(key, value)? {
+            self.stats.l2.evictions += 1;
+            if self.policy == TierPolicy::Inclusive {
+                self.l1.remove(&old_key);
+            }
+        }
+        Ok(())
+    }
+}
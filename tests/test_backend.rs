@@ -0,0 +1,145 @@
+use cache_lru::backend::{BackedCache, Backend, MemoryBackend, WriteMode};
+use cache_lru::storage::PersistentCache;
+use std::fs;
+use std::io;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Stockage dont les écritures échouent
+    struct FailingBackend;
+
+    impl Backend<i32, i32> for FailingBackend {
+        fn load(&mut self, _key: &i32) -> io::Result<Option<i32>> {
+            Ok(None)
+        }
+
+        fn store(&mut self, _key: &i32, _value: &i32) -> io::Result<()> {
+            Err(io::Error::other("écriture impossible"))
+        }
+
+        fn delete(&mut self, _key: &i32) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // On test qu'un élément modifié n'est pas perdu si son écriture échoue à l'éviction
+    #[test]
+    fn test_backed_cache_write_back_failure() {
+        println!("=== On effectue un test d'échec d'écriture à l'éviction ===");
+
+        let mut cache = BackedCache::new(1, FailingBackend, WriteMode::WriteBack);
+        cache.put(1, 100).unwrap();
+        assert!(cache.put(2, 200).is_err());
+        println!("L'écriture de la clé 1 a échoué lors de l'ajout de la clé 2");
+
+        // La clé 1 reste dans le cache et reste à écrire
+        assert!(cache.is_dirty(&1));
+        assert!(!cache.is_dirty(&2));
+        assert_eq!(cache.get(&1).unwrap(), Some(&100));
+        println!("La clé 1 est toujours dans le cache, marquée comme modifiée");
+
+        println!("=== Fin du test d'échec d'écriture à l'éviction ===");
+    }
+
+    // On test la lecture depuis le stockage et l'écriture immédiate
+    #[test]
+    fn test_backed_cache_write_through() {
+        println!("=== On effectue un test en mode write-through ===");
+
+        // On prépare un stockage contenant déjà une valeur
+        let mut backend = MemoryBackend::new();
+        backend.store(&"A", &"value_a".to_string()).unwrap();
+
+        let mut cache = BackedCache::new(2, backend, WriteMode::WriteThrough);
+        println!("Cache initialisé avec une capacité de 2.");
+
+        // La valeur est chargée depuis le stockage lors du défaut de cache
+        assert_eq!(cache.get(&"A").unwrap(), Some(&"value_a".to_string()));
+        println!("La clé A a été chargée depuis le stockage");
+        assert_eq!(cache.get(&"X").unwrap(), None);
+        println!("La clé X n'existe pas dans le stockage");
+
+        // Les écritures sont propagées immédiatement
+        cache.put("B", "value_b".to_string()).unwrap();
+        assert_eq!(cache.backend().value(&"B"), Some(&"value_b".to_string()));
+        assert!(!cache.is_dirty(&"B"));
+        println!("La clé B a été écrite dans le stockage");
+
+        // La suppression retire la clé du stockage
+        cache.remove(&"A").unwrap();
+        assert!(!cache.backend().contains(&"A"));
+        assert_eq!(cache.get(&"A").unwrap(), None);
+        println!("La clé A a été supprimée du cache et du stockage");
+
+        println!("=== Fin du test en mode write-through ===");
+    }
+
+    // On test l'écriture différée lors de l'éviction et du flush
+    #[test]
+    fn test_backed_cache_write_back() {
+        println!("=== On effectue un test en mode write-back ===");
+
+        let mut cache = BackedCache::new(2, MemoryBackend::new(), WriteMode::WriteBack);
+        println!("Cache initialisé avec une capacité de 2.");
+
+        cache.put(1, 100).unwrap();
+        cache.put(2, 200).unwrap();
+        assert!(!cache.backend().contains(&1));
+        assert!(cache.is_dirty(&1));
+        println!("Les clés 1 et 2 ne sont pas encore écrites dans le stockage");
+
+        // L'éviction de la clé 1 déclenche son écriture
+        cache.put(3, 300).unwrap();
+        assert_eq!(cache.backend().value(&1), Some(&100));
+        assert!(!cache.backend().contains(&2));
+        println!("La clé 1 a été écrite lors de son éviction");
+
+        // Le flush écrit les éléments restants
+        cache.flush().unwrap();
+        assert_eq!(cache.backend().value(&2), Some(&200));
+        assert_eq!(cache.backend().value(&3), Some(&300));
+        assert!(!cache.is_dirty(&3));
+        println!("Les clés 2 et 3 ont été écrites lors du flush");
+
+        // La clé évincée est rechargée depuis le stockage
+        assert_eq!(cache.get(&1).unwrap(), Some(&100));
+        println!("La clé 1 a été rechargée depuis le stockage");
+
+        println!("=== Fin du test en mode write-back ===");
+    }
+
+    // On test l'utilisation d'un cache persistant comme stockage
+    #[test]
+    fn test_backed_cache_persistent_backend() {
+        println!("=== On effectue un test avec un cache persistant comme stockage ===");
+
+        let cache_file = "test_backend_persistent.txt";
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+
+        let persistent: PersistentCache<String, String> = PersistentCache::new(10, cache_file);
+        let mut cache = BackedCache::new(1, persistent, WriteMode::WriteBack);
+
+        cache.put("A".to_string(), "value_a".to_string()).unwrap();
+        cache.put("B".to_string(), "value_b".to_string()).unwrap();
+        println!("La clé A a été évincée vers le cache persistant");
+
+        // On relit le fichier avec un nouveau cache persistant
+        let mut reloaded: PersistentCache<String, String> = PersistentCache::new(10, cache_file);
+        reloaded.load().unwrap();
+        assert_eq!(reloaded.get(&"A".to_string()), Some(&"value_a".to_string()));
+        assert_eq!(reloaded.get(&"B".to_string()), None);
+        println!("Le fichier contient la clé A mais pas encore la clé B");
+
+        assert_eq!(
+            cache.get(&"A".to_string()).unwrap(),
+            Some(&"value_a".to_string())
+        );
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+        println!("=== Fin du test avec un cache persistant comme stockage ===");
+    }
+}
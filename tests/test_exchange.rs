@@ -0,0 +1,190 @@
+use cache_lru::cache_lru::Cache;
+use cache_lru::exchange::{ExchangeFormat, Overflow};
+use cache_lru::storage::{MemoryStore, PersistentCache, StorageError, StringCodec};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Cache<String, String> {
+        let mut cache = Cache::new(5);
+        cache.put("simple".to_string(), "valeur".to_string());
+        cache.put("a,b".to_string(), "dit \"bonjour\"".to_string());
+        cache.put("multi".to_string(), "ligne 1\r\nligne 2\ttab".to_string());
+        cache.put("unicode".to_string(), "été \u{1F600} \\ \u{1}".to_string());
+        cache
+    }
+
+    fn keys(cache: &Cache<String, String>) -> Vec<String> {
+        cache.iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    // On test l'export puis l'import dans les deux formats
+    #[test]
+    fn test_exchange_round_trip() {
+        println!("=== On effectue un test de l'export et de l'import ===");
+
+        let cache = sample();
+        for format in [ExchangeFormat::JsonLines, ExchangeFormat::Csv] {
+            let mut out = Vec::new();
+            cache.export(&mut out, format).unwrap();
+            println!("{:?} :\n{}", format, String::from_utf8_lossy(&out));
+
+            let mut imported: Cache<String, String> = Cache::new(5);
+            let report = imported
+                .import(out.as_slice(), format, Overflow::Truncate)
+                .unwrap();
+            assert_eq!((report.imported, report.dropped), (4, 0));
+            assert_eq!(keys(&imported), keys(&cache));
+            for (key, value) in cache.iter() {
+                assert_eq!(imported.peek(key), Some(value));
+            }
+        }
+
+        // Le premier élément exporté est le plus récent
+        let mut out = Vec::new();
+        cache.export(&mut out, ExchangeFormat::JsonLines).unwrap();
+        let first = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            first,
+            "{\"key\":\"unicode\",\"value\":\"été \u{1F600} \\\\ \\u0001\"}"
+        );
+        println!("Les éléments ont été relus à l'identique, dans le même ordre");
+
+        // Fichiers écrits par d'autres outils
+        let json = " { \"value\" : \"\\ud83d\\ude00\", \"autre\": 1, \"key\":\"x\" }\n\n";
+        let mut imported: Cache<String, String> = Cache::new(5);
+        imported
+            .import(
+                json.as_bytes(),
+                ExchangeFormat::JsonLines,
+                Overflow::Truncate,
+            )
+            .unwrap();
+        assert_eq!(
+            imported.peek(&"x".to_string()),
+            Some(&"\u{1F600}".to_string())
+        );
+        let csv = "y,\"sur\ndeux lignes\"\nz,\"\"\n";
+        imported
+            .import(csv.as_bytes(), ExchangeFormat::Csv, Overflow::Truncate)
+            .unwrap();
+        assert_eq!(
+            imported.peek(&"y".to_string()),
+            Some(&"sur\ndeux lignes".to_string())
+        );
+        assert_eq!(imported.peek(&"z".to_string()), Some(&String::new()));
+        println!("Les fichiers sans en-tête ni \\r\\n ont été relus");
+
+        println!("=== Fin du test de l'export et de l'import ===");
+    }
+
+    // On test le traitement des éléments au-delà de la capacité
+    #[test]
+    fn test_exchange_overflow() {
+        println!("=== On effectue un test du dépassement de capacité ===");
+
+        let csv = "key,value\r\nE,5\r\nD,4\r\nC,3\r\nB,2\r\nA,1\r\n";
+
+        // Seule la place libre est utilisée : X reste dans le cache
+        let mut cache: Cache<String, u32> = Cache::new(3);
+        cache.put("X".to_string(), 0);
+        let report = cache
+            .import(csv.as_bytes(), ExchangeFormat::Csv, Overflow::Truncate)
+            .unwrap();
+        assert_eq!((report.imported, report.dropped), (2, 3));
+        let order: Vec<_> = cache.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(order, vec!["E", "D", "X"]);
+        println!("Tronqué : {:?}", order);
+
+        // Les éléments les plus récents du fichier remplacent ceux du cache
+        let mut cache: Cache<String, u32> = Cache::new(3);
+        cache.put("X".to_string(), 0);
+        let report = cache
+            .import(
+                csv.as_bytes(),
+                ExchangeFormat::Csv,
+                Overflow::KeepMostRecent(10),
+            )
+            .unwrap();
+        assert_eq!((report.imported, report.dropped), (3, 2));
+        let order: Vec<_> = cache.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(order, vec!["E", "D", "C"]);
+        println!("Les plus récents : {:?}", order);
+
+        println!("=== Fin du test du dépassement de capacité ===");
+    }
+
+    // On test les erreurs d'import
+    #[test]
+    fn test_exchange_errors() {
+        println!("=== On effectue un test des erreurs d'import ===");
+
+        let mut cache: Cache<String, u32> = Cache::new(3);
+        let cases = [
+            (ExchangeFormat::Csv, "a,1\nb,deux\n", 2),
+            (ExchangeFormat::Csv, "a,1\nb,\"2\n", 2),
+            (ExchangeFormat::Csv, "a,1,3\n", 1),
+            (
+                ExchangeFormat::JsonLines,
+                "{\"key\":\"a\",\"value\":1}\n{\"key\":\"b\"}\n",
+                2,
+            ),
+            (
+                ExchangeFormat::JsonLines,
+                "{\"key\":\"a\",\"value\":\"\\q\"}\n",
+                1,
+            ),
+        ];
+        for (format, input, line) in cases {
+            match cache.import(input.as_bytes(), format, Overflow::Truncate) {
+                Err(StorageError::Parse(bad_line)) => {
+                    println!("Erreur attendue : {}", bad_line);
+                    assert_eq!(bad_line.line, line);
+                }
+                other => panic!("Erreur de parsing attendue, obtenu : {:?}", other),
+            }
+        }
+        assert!(cache.is_empty());
+        println!("Aucun élément n'a été importé");
+
+        println!("=== Fin du test des erreurs d'import ===");
+    }
+
+    // On test l'import et l'export d'un cache persistant
+    #[test]
+    fn test_exchange_persistent() {
+        println!("=== On effectue un test avec un cache persistant ===");
+
+        let store = MemoryStore::new();
+        let mut cache: PersistentCache<String, String> =
+            PersistentCache::with_store(3, store.clone(), StringCodec, StringCodec);
+        let json = "{\"key\":\"B\",\"value\":\"b\"}\n{\"key\":\"A\",\"value\":\"a\"}\n";
+        let report = cache
+            .import(
+                json.as_bytes(),
+                ExchangeFormat::JsonLines,
+                Overflow::Truncate,
+            )
+            .unwrap();
+        assert_eq!(report.imported, 2);
+        drop(cache);
+
+        // Les éléments importés ont été enregistrés
+        let reopened: PersistentCache<String, String> =
+            PersistentCache::open_with_store(3, store, StringCodec, StringCodec).unwrap();
+        let mut out = Vec::new();
+        reopened
+            .export(&mut out, ExchangeFormat::JsonLines)
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), json);
+        println!("Les éléments importés ont été rechargés puis exportés");
+
+        println!("=== Fin du test avec un cache persistant ===");
+    }
+}
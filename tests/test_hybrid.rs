@@ -0,0 +1,143 @@
+use cache_lru::hybrid::HybridCache;
+use std::fs;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(dir: &str) -> usize {
+        fs::read_dir(dir)
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with("segment-")
+            })
+            .count()
+    }
+
+    // On test le placement des valeurs en mémoire ou sur disque
+    #[test]
+    fn test_hybrid_cache_spill() {
+        println!("=== On effectue un test du placement des valeurs ===");
+
+        let dir = "test_hybrid_spill";
+        let _ = fs::remove_dir_all(dir);
+
+        let mut cache: HybridCache<String, String> = HybridCache::open(3, dir).unwrap();
+        cache.set_spill_threshold(8);
+        cache.put("petit".to_string(), "court".to_string()).unwrap();
+        cache.put("grand".to_string(), "x".repeat(100)).unwrap();
+        println!("Ajouté : une petite et une grande valeur");
+
+        assert!(!cache.is_on_disk(&"petit".to_string()));
+        assert!(cache.is_on_disk(&"grand".to_string()));
+        assert_eq!(cache.live_disk_bytes(), 100);
+        assert_eq!(
+            cache.get(&"grand".to_string()).unwrap(),
+            Some("x".repeat(100))
+        );
+        assert_eq!(
+            cache.get(&"petit".to_string()).unwrap(),
+            Some("court".to_string())
+        );
+        println!("La grande valeur a été relue depuis le disque");
+
+        // L'éviction libère la valeur sur disque
+        cache.put("C".to_string(), "c".to_string()).unwrap();
+        cache.put("D".to_string(), "d".to_string()).unwrap();
+        assert!(!cache.contains(&"grand".to_string()));
+        assert_eq!(cache.live_disk_bytes(), 0);
+        println!("La grande valeur a été évincée");
+
+        let _ = fs::remove_dir_all(dir);
+        println!("=== Fin du test du placement des valeurs ===");
+    }
+
+    // On test la compaction des segments et le budget d'espace disque
+    #[test]
+    fn test_hybrid_cache_compaction_and_budget() {
+        println!("=== On effectue un test de la compaction et du budget ===");
+
+        let dir = "test_hybrid_budget";
+        let _ = fs::remove_dir_all(dir);
+
+        let mut cache: HybridCache<String, String> = HybridCache::open(10, dir).unwrap();
+        cache.set_spill_threshold(0);
+        cache.set_segment_size(20);
+        for key in ["A", "B", "C", "D"] {
+            cache.put(key.to_string(), key.repeat(10)).unwrap();
+        }
+        assert_eq!(segments(dir), 2);
+        println!("4 valeurs de 10 octets réparties en 2 segments");
+
+        // Un segment dont toutes les valeurs sont mortes est supprimé
+        cache.remove(&"A".to_string()).unwrap();
+        cache.remove(&"B".to_string()).unwrap();
+        assert_eq!(segments(dir), 1);
+
+        cache.remove(&"C".to_string()).unwrap();
+        assert_eq!(cache.disk_usage(), 20);
+        cache.compact().unwrap();
+        assert_eq!(cache.disk_usage(), 10);
+        assert_eq!(cache.get(&"D".to_string()).unwrap(), Some("D".repeat(10)));
+        println!("La compaction a récupéré l'espace de C");
+
+        // Le budget retire les éléments sur disque les moins récemment utilisés
+        cache.put("E".to_string(), "E".repeat(10)).unwrap();
+        cache.get(&"D".to_string()).unwrap();
+        cache.set_disk_budget(Some(25)).unwrap();
+        cache.put("F".to_string(), "F".repeat(10)).unwrap();
+        assert!(!cache.contains(&"E".to_string()));
+        assert!(cache.contains(&"D".to_string()));
+        assert!(cache.contains(&"F".to_string()));
+        assert!(cache.disk_usage() <= 25);
+        println!("E, le moins récemment utilisé, a été retiré pour respecter le budget");
+
+        let _ = fs::remove_dir_all(dir);
+        println!("=== Fin du test de la compaction et du budget ===");
+    }
+
+    // On test la suppression des segments orphelins
+    #[test]
+    fn test_hybrid_cache_orphans() {
+        println!("=== On effectue un test des segments orphelins ===");
+
+        let dir = "test_hybrid_orphans";
+        let _ = fs::remove_dir_all(dir);
+        fs::create_dir_all(dir).unwrap();
+        fs::write(format!("{}/segment-7", dir), "ancien").unwrap();
+        fs::write(format!("{}/autre.txt", dir), "conservé").unwrap();
+
+        let mut cache: HybridCache<String, String> = HybridCache::open(3, dir).unwrap();
+        assert!(!fs::exists(format!("{}/segment-7", dir)).unwrap());
+        assert!(fs::exists(format!("{}/autre.txt", dir)).unwrap());
+        println!("Le segment d'une exécution précédente a été supprimé");
+
+        cache.set_spill_threshold(0);
+        cache.put("A".to_string(), "Valeur A".to_string()).unwrap();
+        fs::write(format!("{}/segment-99", dir), "orphelin").unwrap();
+        assert_eq!(cache.remove_orphans().unwrap(), 1);
+        assert_eq!(
+            cache.get(&"A".to_string()).unwrap(),
+            Some("Valeur A".to_string())
+        );
+        println!("Seul le segment inutilisé a été supprimé");
+
+        // Le dossier ne peut pas être utilisé par un second cache en même temps
+        assert!(HybridCache::<String, String>::open(3, dir).is_err());
+        assert_eq!(
+            cache.get(&"A".to_string()).unwrap(),
+            Some("Valeur A".to_string())
+        );
+        drop(cache);
+        assert!(HybridCache::<String, String>::open(3, dir).is_ok());
+        println!("Le dossier est verrouillé tant que le cache existe");
+
+        let _ = fs::remove_dir_all(dir);
+        println!("=== Fin du test des segments orphelins ===");
+    }
+}
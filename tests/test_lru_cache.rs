@@ -1,4 +1,6 @@
-use cache_lru::cache_lru::Cache;
+use cache_lru::cache_lru::{Cache, Lookup};
+use std::thread;
+use std::time::Duration;
 
 #[cfg(test)]
 mod tests {
@@ -155,4 +157,134 @@ mod tests {
 
         println!("=== Fin du test avec des valeurs de type int ===");
     }
+
+    // On test la mémorisation des clés absentes de la source
+    #[test]
+    fn test_lru_cache_negative() {
+        println!("=== On effectue un test de mémorisation des clés absentes ===");
+
+        let mut cache = Cache::new(2);
+        cache.set_negative_caching(Duration::from_millis(100), 2);
+        println!("Cache initialisé avec une capacité de 2 et 2 marqueurs d'absence.");
+
+        cache.put("A", 1);
+        cache.put_absent("X");
+        cache.put_absent("Y");
+        println!("Ajouté : A -> 1, X et Y sont absentes de la source");
+
+        // On distingue les clés présentes, absentes et inconnues
+        assert_eq!(cache.lookup(&"A"), Lookup::Present(&1));
+        assert_eq!(cache.lookup(&"X"), Lookup::Absent);
+        assert_eq!(cache.lookup(&"Z"), Lookup::Unknown);
+        assert_eq!(cache.get(&"X"), None);
+
+        // Les marqueurs ont leur propre capacité et n'évincent pas les valeurs
+        cache.put_absent("Z");
+        assert_eq!(cache.lookup(&"X"), Lookup::Unknown);
+        assert_eq!(cache.lookup(&"A"), Lookup::Present(&1));
+        println!("Le marqueur de X a été retiré, A est toujours présente");
+
+        // Un put efface le marqueur d'absence
+        cache.put("Y", 2);
+        assert_eq!(cache.lookup(&"Y"), Lookup::Present(&2));
+        println!("Ajouté : Y -> 2, le marqueur de Y a été effacé");
+
+        // Les marqueurs expirent
+        thread::sleep(Duration::from_millis(150));
+        assert_eq!(cache.lookup(&"Z"), Lookup::Unknown);
+        println!("Le marqueur de Z a expiré");
+
+        println!("=== Fin du test de mémorisation des clés absentes ===");
+    }
+
+    // On test l'invalidation d'un groupe d'éléments par tag
+    #[test]
+    fn test_lru_cache_tags() {
+        println!("=== On effectue un test d'invalidation par tag ===");
+
+        let mut cache = Cache::new(3);
+        cache.put_tagged("user:42:profile", 1, &["user:42"]);
+        cache.put_tagged("user:42:posts", 2, &["user:42", "posts"]);
+        cache.put_tagged("user:7:posts", 3, &["user:7", "posts"]);
+        println!("Ajouté : trois éléments dépendant des utilisateurs 42 et 7");
+
+        assert_eq!(
+            cache.tags(&"user:42:posts"),
+            Some(&["user:42".to_string(), "posts".to_string()][..])
+        );
+
+        // On retire tous les éléments du tag user:42
+        assert_eq!(cache.invalidate_tag("user:42"), 2);
+        assert_eq!(cache.get(&"user:42:profile"), None);
+        assert_eq!(cache.get(&"user:42:posts"), None);
+        assert_eq!(cache.get(&"user:7:posts"), Some(&3));
+        println!("Les éléments du tag user:42 ont été retirés");
+
+        // Un tag inconnu ne retire rien
+        assert_eq!(cache.invalidate_tag("user:42"), 0);
+
+        // L'éviction nettoie l'index des tags
+        cache.put("A", 10);
+        cache.put("B", 20);
+        cache.put("C", 30);
+        println!("Ajouté : A, B et C, user:7:posts a été évincé");
+        cache.put("user:7:posts", 4);
+        assert_eq!(cache.invalidate_tag("posts"), 0);
+        assert_eq!(cache.get(&"user:7:posts"), Some(&4));
+        println!("Le tag posts ne référence plus l'élément évincé");
+
+        println!("=== Fin du test d'invalidation par tag ===");
+    }
+
+    // On test le parcours et le retrait d'intervalles de clés
+    #[test]
+    fn test_lru_cache_ordered_index() {
+        println!("=== On effectue un test de l'index ordonné ===");
+
+        let mut cache = Cache::new(4);
+        cache.enable_ordered_index();
+        cache.put("user:42:profile".to_string(), 1);
+        cache.put("user:42:posts".to_string(), 2);
+        cache.put("user:7:profile".to_string(), 3);
+        cache.put("user:8:profile".to_string(), 4);
+        println!("Ajouté : quatre clés hiérarchiques");
+
+        // L'éviction retire la clé de l'index
+        cache.put("user:9:profile".to_string(), 5);
+        println!("Ajouté : user:9:profile, user:42:profile a été évincé");
+        let keys: Vec<&String> = cache
+            .range("user:4".to_string()..)
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "user:42:posts",
+                "user:7:profile",
+                "user:8:profile",
+                "user:9:profile"
+            ]
+        );
+
+        // On retire toutes les clés user:42:*
+        assert_eq!(cache.remove_prefix("user:42:"), 1);
+        assert_eq!(cache.get(&"user:42:posts".to_string()), None);
+        println!("Les clés user:42:* ont été retirées");
+
+        // On retire un intervalle de clés
+        assert_eq!(
+            cache.remove_range("user:7".to_string().."user:9".to_string()),
+            2
+        );
+        let keys: Vec<&String> = cache.range(..).map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["user:9:profile"]);
+        println!("Les clés de user:7 à user:9 exclu ont été retirées");
+
+        // La mise à jour d'une clé ne la duplique pas dans l'index
+        cache.put("user:9:profile".to_string(), 6);
+        assert_eq!(cache.range(..).count(), 1);
+        assert_eq!(cache.remove_prefix("user:"), 1);
+
+        println!("=== Fin du test de l'index ordonné ===");
+    }
 }
@@ -1,5 +1,38 @@
-use cache_lru::storage::PersistentCache;
+use cache_lru::backend::WriteMode;
+use cache_lru::storage::migration::Migrations;
+use cache_lru::storage::{
+    format, ArchiveNaming, BytesCodec, Codec, EntryMeta, IntCodec, LoadMode, LoadOptions,
+    MemoryStore, PersistentCache, Sharing, SnapshotFormat, StorageError, StringCodec,
+};
 use std::fs;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+// Conversion d'un point en octets, pour tester une conversion définie par l'utilisateur
+#[derive(Debug, Clone, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct PointCodec;
+
+impl Codec<Point> for PointCodec {
+    fn encode(&self, point: &Point) -> Vec<u8> {
+        let mut bytes = IntCodec.encode(&point.x);
+        bytes.extend(IntCodec.encode(&point.y));
+        bytes
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Point, String> {
+        let (x, y) = bytes.split_at_checked(4).ok_or("point trop court")?;
+        Ok(Point {
+            x: IntCodec.decode(x)?,
+            y: IntCodec.decode(y)?,
+        })
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -14,7 +47,8 @@ mod tests {
         let cache_file = "test_cache_string.txt";
         println!("Le fichier utilisé est le suivant : {}", cache_file);
 
-        // On supprime le fichier pour que les tests soit propre au début du test
+        // On supprime le fichier et son journal pour que les tests soit propre au début du test
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
         if fs::remove_file(cache_file).is_ok() {
             println!("Fichier précédent '{}' supprimé avec succès.", cache_file);
         } else {
@@ -26,13 +60,13 @@ mod tests {
         println!("Cache initialisé avec une capacité de 3.");
 
         // On ajoute des valeurs
-        cache.put("A".to_string(), "value_a".to_string());
+        cache.put("A".to_string(), "value_a".to_string()).unwrap();
         println!("Ajouté : A -> value_a");
-        cache.put("B".to_string(), "value_b".to_string());
+        cache.put("B".to_string(), "value_b".to_string()).unwrap();
         println!("Ajouté : B -> value_b");
-        cache.put("C".to_string(), "value_c".to_string());
+        cache.put("C".to_string(), "value_c".to_string()).unwrap();
         println!("Ajouté : C -> value_c");
-        cache.put("D".to_string(), "value_d".to_string());
+        cache.put("D".to_string(), "value_d".to_string()).unwrap();
         println!("Ajouté : D -> value_d, A a été supprimé)");
 
         println!("----------------------------------");
@@ -89,6 +123,7 @@ mod tests {
 
         // On supprime le fichier pour nettoyer après le test
         println!("\n--- Nettoyage après les tests ---");
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
         if fs::remove_file(cache_file).is_ok() {
             println!("Fichier '{}' supprimé après le test", cache_file);
         } else {
@@ -108,7 +143,8 @@ mod tests {
         let cache_file_int = "test_cache_int.txt";
         println!("Le fichier utilisé est le suivant : {}", cache_file_int);
 
-        // On supprime le fichier pour que les tests soit propre au début du test
+        // On supprime le fichier et son journal pour que les tests soit propre au début du test
+        let _ = fs::remove_file(format!("{}.wal", cache_file_int));
         if fs::remove_file(cache_file_int).is_ok() {
             println!(
                 "Fichier précédent '{}' supprimé avec succès",
@@ -123,11 +159,11 @@ mod tests {
         println!("Cache initialisé avec une capacité de 2.");
 
         // On ajoute des valeurs
-        cache_int.put(1, 100);
+        cache_int.put(1, 100).unwrap();
         println!("Ajouté : 1 -> 100");
-        cache_int.put(2, 200);
+        cache_int.put(2, 200).unwrap();
         println!("Ajouté : 2 -> 200");
-        cache_int.put(3, 300);
+        cache_int.put(3, 300).unwrap();
         println!("Ajouté : 3 -> 300, 1 a été supprimé");
 
         println!("----------------------------------");
@@ -182,6 +218,7 @@ mod tests {
 
         // On supprime le fichier pour nettoyer après le test
         println!("\n--- Nettoyage après les tests ---");
+        let _ = fs::remove_file(format!("{}.wal", cache_file_int));
         if fs::remove_file(cache_file_int).is_ok() {
             println!("Fichier '{}' supprimé après le test", cache_file_int);
         } else {
@@ -190,4 +227,777 @@ mod tests {
 
         println!("=== Fin du test avec des valeurs de type int ===");
     }
+
+    // On test que les clés et valeurs contenant des caractères spéciaux sont relues à l'identique
+    #[test]
+    fn test_lru_cache_persistent_escaping() {
+        println!("=== On effectue un test avec des caractères spéciaux ===");
+
+        let cache_file = "test_cache_escaping.txt";
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+
+        let mut cache = PersistentCache::new(3, cache_file);
+        cache
+            .put("http://x".to_string(), "ligne 1\nligne 2\r\n".to_string())
+            .unwrap();
+        cache.put("a:b\\c".to_string(), "".to_string()).unwrap();
+        cache.put("".to_string(), "::".to_string()).unwrap();
+        println!(
+            "Contenu du journal :\n{}",
+            fs::read_to_string(format!("{}.wal", cache_file)).unwrap()
+        );
+
+        let mut cache_charge: PersistentCache<String, String> = PersistentCache::new(3, cache_file);
+        cache_charge.load().unwrap();
+        assert_eq!(
+            cache_charge.get(&"http://x".to_string()),
+            Some(&"ligne 1\nligne 2\r\n".to_string())
+        );
+        assert_eq!(
+            cache_charge.get(&"a:b\\c".to_string()),
+            Some(&"".to_string())
+        );
+        assert_eq!(cache_charge.get(&"".to_string()), Some(&"::".to_string()));
+        println!("Les éléments ont été relus à l'identique");
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+        println!("=== Fin du test avec des caractères spéciaux ===");
+    }
+
+    // On test la lecture des anciens fichiers et le refus des versions inconnues
+    #[test]
+    fn test_lru_cache_persistent_versions() {
+        println!("=== On effectue un test des versions du format ===");
+
+        // Un ancien fichier sans en-tête est toujours lisible
+        let cache_file = "test_cache_legacy.txt";
+        fs::write(cache_file, "A:value_a\nB:http://b\n").unwrap();
+        let mut cache: PersistentCache<String, String> = PersistentCache::new(3, cache_file);
+        cache.load().unwrap();
+        assert_eq!(cache.get(&"A".to_string()), Some(&"value_a".to_string()));
+        assert_eq!(cache.get(&"B".to_string()), Some(&"http://b".to_string()));
+        println!("L'ancien fichier a été chargé");
+
+        // Le fichier est réécrit avec un en-tête
+        cache.save().unwrap();
+        let content = fs::read_to_string(cache_file).unwrap();
+        assert!(content.starts_with("#cache_lru v3 capacity=3 policy=lru "));
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+
+        // Une version plus récente est refusée
+        let cache_file = "test_cache_future.txt";
+        fs::write(cache_file, "#cache_lru v99\nA:value_a\n").unwrap();
+        let mut cache: PersistentCache<String, String> = PersistentCache::new(3, cache_file);
+        assert!(matches!(
+            cache.load(),
+            Err(StorageError::UnsupportedVersion {
+                found: 99,
+                supported: 3
+            })
+        ));
+        assert_eq!(cache.get(&"A".to_string()), None);
+        println!("Le fichier d'une version inconnue a été refusé");
+
+        let _ = fs::remove_file(cache_file);
+        println!("=== Fin du test des versions du format ===");
+    }
+
+    // On test les erreurs de persistance sans panique
+    #[test]
+    fn test_lru_cache_persistent_errors() {
+        println!("=== On effectue un test des erreurs de persistance ===");
+
+        // Une ligne invalide fait échouer le chargement strict sans rien charger
+        let cache_file = "test_cache_errors.txt";
+        fs::write(cache_file, "#cache_lru v2\n1:100\ndeux:200\n3\n4:400\n").unwrap();
+        let mut cache: PersistentCache<i32, i32> = PersistentCache::new(5, cache_file);
+        match cache.load() {
+            Err(StorageError::Parse(bad_line)) => {
+                println!("Erreur attendue : {}", bad_line);
+                assert_eq!(bad_line.line, 3);
+                assert_eq!(bad_line.text, "deux:200");
+            }
+            other => panic!("Erreur de parsing attendue, obtenu : {:?}", other),
+        }
+        assert_eq!(cache.get(&1), None);
+
+        // Le chargement tolérant ignore les lignes invalides et les signale
+        let report = cache.load_with(LoadOptions::skip_bad_lines()).unwrap();
+        println!("Rapport de chargement : {:?}", report);
+        assert_eq!(report.loaded, 2);
+        let skipped: Vec<usize> = report.skipped.iter().map(|bad| bad.line).collect();
+        assert_eq!(skipped, vec![3, 4]);
+        assert_eq!(cache.get(&1), Some(&100));
+        assert_eq!(cache.get(&4), Some(&400));
+        let _ = fs::remove_file(cache_file);
+
+        // Une erreur d'écriture est retournée au lieu de paniquer
+        let mut cache = PersistentCache::new(2, "dossier_inexistant/cache.txt");
+        let result = cache.put(1, 100);
+        assert!(matches!(result, Err(StorageError::Io(_))));
+        assert_eq!(cache.get(&1), Some(&100));
+        println!("L'erreur d'écriture a été retournée");
+
+        println!("=== Fin du test des erreurs de persistance ===");
+    }
+
+    // On test le journal : ajout en fin de fichier, relecture à l'ouverture et compaction
+    #[test]
+    fn test_lru_cache_persistent_log() {
+        println!("=== On effectue un test du journal ===");
+
+        let cache_file = "test_cache_log.txt";
+        let log_file = format!("{}.wal", cache_file);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+
+        // Chaque opération ajoute une ligne au journal, sans écrire le fichier de sauvegarde
+        let mut cache = PersistentCache::new(3, cache_file);
+        cache.set_compaction_threshold(100);
+        cache.put(1, 100).unwrap();
+        let size = fs::metadata(&log_file).unwrap().len();
+        cache.put(2, 200).unwrap();
+        let step = fs::metadata(&log_file).unwrap().len() - size;
+        cache.put(3, 300).unwrap();
+        assert_eq!(fs::metadata(&log_file).unwrap().len(), size + 2 * step);
+        assert!(!std::path::Path::new(cache_file).exists());
+        cache.touch(&1).unwrap();
+        cache.remove(&2).unwrap();
+        cache.put(4, 400).unwrap();
+        cache.put(5, 500).unwrap();
+        assert_eq!(cache.log_len(), 7);
+        println!("Journal :\n{}", fs::read_to_string(&log_file).unwrap());
+
+        // Le journal est rejoué à l'ouverture : 3 a été évincé car 1 a été utilisé
+        let mut reopened: PersistentCache<i32, i32> = PersistentCache::open(3, cache_file).unwrap();
+        assert_eq!(reopened.get(&1), Some(&100));
+        assert_eq!(reopened.get(&2), None);
+        assert_eq!(reopened.get(&3), None);
+        assert_eq!(reopened.get(&5), Some(&500));
+        println!("Le journal a été rejoué");
+
+        // Une dernière ligne interrompue est ignorée
+        fs::write(
+            &log_file,
+            format!("{}P 6:6", fs::read_to_string(&log_file).unwrap()),
+        )
+        .unwrap();
+        let mut reopened: PersistentCache<i32, i32> = PersistentCache::open(3, cache_file).unwrap();
+        assert_eq!(reopened.get(&6), None);
+        assert_eq!(reopened.get(&4), Some(&400));
+        println!("La ligne interrompue a été ignorée");
+
+        // Le journal est compacté dans le fichier de sauvegarde quand le seuil est atteint
+        reopened.set_compaction_threshold(reopened.log_len() + 1);
+        reopened.put(7, 700).unwrap();
+        assert_eq!(reopened.log_len(), 0);
+        assert!(fs::read_to_string(cache_file).unwrap().contains("7:700"));
+        let mut compacted: PersistentCache<i32, i32> =
+            PersistentCache::open(3, cache_file).unwrap();
+        assert_eq!(compacted.get(&7), Some(&700));
+        assert_eq!(compacted.get(&6), None);
+        println!("Le journal a été compacté");
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        println!("=== Fin du test du journal ===");
+    }
+
+    // On simule un arrêt brutal pendant une sauvegarde
+    #[test]
+    fn test_lru_cache_persistent_crash() {
+        println!("=== On effectue un test d'arrêt brutal pendant une sauvegarde ===");
+
+        let cache_file = "test_cache_crash.txt";
+        let log_file = format!("{}.wal", cache_file);
+        let temp_file = format!("{}.tmp", cache_file);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+
+        let mut cache = PersistentCache::new(3, cache_file);
+        cache.put(1, 100).unwrap();
+        cache.put(2, 200).unwrap();
+        cache.save().unwrap();
+        assert!(!std::path::Path::new(&temp_file).exists());
+        let saved = fs::read_to_string(cache_file).unwrap();
+
+        // L'arrêt survient après l'écriture partielle du fichier temporaire
+        fs::write(&temp_file, "#cache_lru v2\n3:30").unwrap();
+        assert_eq!(fs::read_to_string(cache_file).unwrap(), saved);
+
+        // À l'ouverture, l'ancienne sauvegarde est chargée et le fichier temporaire supprimé
+        let mut reopened: PersistentCache<i32, i32> = PersistentCache::open(3, cache_file).unwrap();
+        assert_eq!(reopened.get(&1), Some(&100));
+        assert_eq!(reopened.get(&2), Some(&200));
+        assert_eq!(reopened.get(&3), None);
+        assert!(!std::path::Path::new(&temp_file).exists());
+        println!("La sauvegarde précédente est intacte");
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        println!("=== Fin du test d'arrêt brutal ===");
+    }
+
+    // On test le format binaire et la reprise après une corruption
+    #[test]
+    fn test_lru_cache_persistent_binary() {
+        println!("=== On effectue un test du format binaire ===");
+
+        let cache_file = "test_cache_binary.bin";
+        let log_file = format!("{}.wal", cache_file);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+
+        let mut cache = PersistentCache::new(3, cache_file);
+        cache.set_format(SnapshotFormat::Binary);
+        cache
+            .put("A".to_string(), "ligne 1\nligne 2".to_string())
+            .unwrap();
+        cache.put("B".to_string(), "value_b".to_string()).unwrap();
+        cache.put("C".to_string(), "value_c".to_string()).unwrap();
+        cache.save().unwrap();
+        let bytes = fs::read(cache_file).unwrap();
+        assert!(bytes.starts_with(b"CLRU"));
+
+        // Le fichier binaire est relu à l'identique
+        let mut reloaded: PersistentCache<String, String> =
+            PersistentCache::open(3, cache_file).unwrap();
+        assert_eq!(
+            reloaded.get(&"A".to_string()),
+            Some(&"ligne 1\nligne 2".to_string())
+        );
+        assert_eq!(reloaded.get(&"B".to_string()), Some(&"value_b".to_string()));
+        println!("Le fichier binaire a été relu");
+
+        // Le deuxième élément est corrompu : seul le premier est relu, la suite est ignorée
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize
+        };
+        let header_len = 12 + u32_at(8);
+        let meta_len = u32_at(header_len + 4 + 1 + 4 + "value_c".len());
+        let record_len = 4 + 1 + 4 + "value_c".len() + 4 + meta_len + 4;
+        let mut corrupted = bytes.clone();
+        corrupted[header_len + record_len + 6] ^= 0xFF;
+        fs::write(cache_file, &corrupted).unwrap();
+        let _ = fs::remove_file(&log_file);
+        let mut reloaded: PersistentCache<String, String> = PersistentCache::new(3, cache_file);
+        let report = reloaded.load().unwrap();
+        println!("Rapport de chargement : {:?}", report);
+        assert_eq!(report.loaded, 1);
+        assert_eq!(
+            report.discarded_bytes,
+            bytes.len() - header_len - record_len
+        );
+        assert_eq!(reloaded.get(&"C".to_string()), Some(&"value_c".to_string()));
+        assert_eq!(reloaded.get(&"B".to_string()), None);
+
+        // Une écriture interrompue au milieu d'un élément est aussi ignorée
+        fs::write(cache_file, &bytes[..bytes.len() - 3]).unwrap();
+        let mut reloaded: PersistentCache<String, String> = PersistentCache::new(3, cache_file);
+        let report = reloaded.load().unwrap();
+        assert_eq!(report.loaded, 2);
+        assert!(report.discarded_bytes > 0);
+        println!("Les éléments corrompus ont été ignorés");
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        println!("=== Fin du test du format binaire ===");
+    }
+
+    // On test que l'ordre d'utilisation est conservé au chargement
+    #[test]
+    fn test_lru_cache_persistent_recency() {
+        println!("=== On effectue un test de l'ordre d'utilisation après chargement ===");
+
+        let cache_file = "test_cache_recency.txt";
+        let log_file = format!("{}.wal", cache_file);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+
+        // Ordre d'utilisation, du plus récent au plus ancien : 1, 3, 2
+        let mut cache = PersistentCache::new(3, cache_file);
+        cache.put(1, 100).unwrap();
+        cache.put(2, 200).unwrap();
+        cache.put(3, 300).unwrap();
+        cache.get(&1);
+        cache.save().unwrap();
+        let saved = fs::read_to_string(cache_file).unwrap();
+        let empty_log = fs::read_to_string(&log_file).unwrap();
+
+        // Après chargement, l'élément le moins récent (2) est le premier retiré,
+        // et le chargement n'a rien écrit
+        let mut reloaded: PersistentCache<i32, i32> = PersistentCache::new(3, cache_file);
+        reloaded.load().unwrap();
+        assert_eq!(fs::read_to_string(cache_file).unwrap(), saved);
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), empty_log);
+        assert_eq!(reloaded.push(4, 400).unwrap(), Some((2, 200)));
+        assert_eq!(reloaded.push(5, 500).unwrap(), Some((3, 300)));
+        println!("L'ordre d'utilisation a été conservé");
+
+        // Par défaut, le chargement remplace le contenu actuel du cache
+        fs::write(&log_file, &empty_log).unwrap();
+        reloaded.load().unwrap();
+        assert_eq!(reloaded.get(&4), None);
+        assert_eq!(reloaded.get(&2), Some(&200));
+
+        // En mode fusion, le contenu du fichier s'ajoute au contenu actuel
+        let mut merged = PersistentCache::new(4, cache_file);
+        merged.put(9, 900).unwrap();
+        fs::write(&log_file, &empty_log).unwrap();
+        let options = LoadOptions::strict().with_mode(LoadMode::Merge);
+        let report = merged.load_with(options).unwrap();
+        assert_eq!(report.loaded, 3);
+        assert_eq!(merged.push(8, 800).unwrap(), Some((9, 900)));
+        assert_eq!(merged.get(&1), Some(&100));
+        println!("Les modes de chargement ont été vérifiés");
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        println!("=== Fin du test de l'ordre d'utilisation ===");
+    }
+
+    // On test la conversion des clés et des valeurs en octets
+    #[test]
+    fn test_lru_cache_persistent_codecs() {
+        println!("=== On effectue un test des conversions en octets ===");
+
+        for format in [SnapshotFormat::Text, SnapshotFormat::Binary] {
+            let cache_file = "test_cache_codecs.txt";
+            let log_file = format!("{}.wal", cache_file);
+            let _ = fs::remove_file(cache_file);
+            let _ = fs::remove_file(&log_file);
+
+            // Des octets bruts, qui ne sont pas de l'UTF-8 valide, avec des clés entières
+            let mut cache = PersistentCache::with_codecs(3, cache_file, IntCodec, BytesCodec);
+            cache.set_format(format);
+            cache.put(1u64, vec![0xFF, b':', b'\n', 0]).unwrap();
+            cache.put(2u64, Vec::new()).unwrap();
+            let mut replayed: PersistentCache<u64, Vec<u8>, _, _> =
+                PersistentCache::open_with_codecs(3, cache_file, IntCodec, BytesCodec).unwrap();
+            assert_eq!(replayed.get(&1), Some(&vec![0xFF, b':', b'\n', 0]));
+            cache.save().unwrap();
+            let mut reloaded: PersistentCache<u64, Vec<u8>, _, _> =
+                PersistentCache::open_with_codecs(3, cache_file, IntCodec, BytesCodec).unwrap();
+            assert_eq!(reloaded.get(&1), Some(&vec![0xFF, b':', b'\n', 0]));
+            assert_eq!(reloaded.get(&2), Some(&Vec::new()));
+            println!("Les octets bruts ont été relus au format {:?}", format);
+
+            // Une structure avec une conversion définie par l'utilisateur
+            let _ = fs::remove_file(cache_file);
+            let _ = fs::remove_file(&log_file);
+            let mut cache = PersistentCache::with_codecs(3, cache_file, IntCodec, PointCodec);
+            cache.set_format(format);
+            cache.put(7i32, Point { x: -1, y: 2 }).unwrap();
+            cache.save().unwrap();
+            let mut reloaded: PersistentCache<i32, Point, _, _> =
+                PersistentCache::open_with_codecs(3, cache_file, IntCodec, PointCodec).unwrap();
+            assert_eq!(reloaded.get(&7), Some(&Point { x: -1, y: 2 }));
+            println!("La structure a été relue au format {:?}", format);
+
+            let _ = fs::remove_file(cache_file);
+            let _ = fs::remove_file(&log_file);
+        }
+
+        // Les entiers dont la taille dépend de la plateforme ont une taille fixe
+        assert_eq!(IntCodec.encode(&7usize).len(), 8);
+        assert_eq!(
+            Codec::<isize>::decode(&IntCodec, &IntCodec.encode(&-7isize)),
+            Ok(-7)
+        );
+        assert!(Codec::<usize>::decode(&IntCodec, &[7, 0, 0, 0]).is_err());
+        println!("Les usize et isize sont enregistrés sur 8 octets");
+
+        println!("=== Fin du test des conversions en octets ===");
+    }
+
+    // On test l'écriture différée des modifications
+    #[test]
+    fn test_lru_cache_persistent_write_back() {
+        println!("=== On effectue un test de l'écriture différée ===");
+
+        let cache_file = "test_cache_write_back.txt";
+        let log_file = format!("{}.wal", cache_file);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+
+        let mut cache = PersistentCache::new(3, cache_file);
+        cache.set_write_mode(WriteMode::WriteBack);
+        cache.set_flush_threshold(10);
+
+        // Les modifications d'une même clé sont regroupées et écrites par `flush`
+        cache.put(1, 10).unwrap();
+        cache.put(2, 20).unwrap();
+        cache.put(1, 11).unwrap();
+        assert_eq!(cache.dirty_len(), 2);
+        assert_eq!(cache.log_len(), 0);
+        cache.flush().unwrap();
+        assert_eq!(cache.dirty_len(), 0);
+        assert_eq!(cache.log_len(), 2);
+        println!(
+            "Journal après flush :\n{}",
+            fs::read_to_string(&log_file).unwrap()
+        );
+
+        // Les modifications sont écrites quand le nombre de clés modifiées est atteint
+        cache.set_flush_threshold(2);
+        cache.put(3, 30).unwrap();
+        assert_eq!(cache.dirty_len(), 1);
+        cache.put(4, 40).unwrap();
+        assert_eq!(cache.dirty_len(), 0);
+        assert_eq!(cache.log_len(), 4);
+
+        // ... ou quand le délai est écoulé
+        cache.set_flush_threshold(10);
+        cache.set_flush_interval(Some(Duration::ZERO));
+        cache.put(5, 50).unwrap();
+        assert_eq!(cache.log_len(), 5);
+        cache.set_flush_interval(None);
+        println!("Les seuils d'écriture ont été vérifiés");
+
+        // Les lectures peuvent aussi marquer l'ordre comme modifié ; tout est écrit
+        // à la destruction du cache
+        cache.set_track_reads(true);
+        assert_eq!(cache.get(&3), Some(&30));
+        cache.put(6, 60).unwrap();
+        assert_eq!(cache.dirty_len(), 2);
+        drop(cache);
+
+        let mut reopened: PersistentCache<i32, i32> = PersistentCache::open(3, cache_file).unwrap();
+        assert_eq!(reopened.get(&4), None);
+        assert_eq!(reopened.get(&3), Some(&30));
+        assert_eq!(reopened.get(&5), Some(&50));
+        assert_eq!(reopened.get(&6), Some(&60));
+        println!("Les modifications en attente ont été écrites à la destruction");
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        println!("=== Fin du test de l'écriture différée ===");
+    }
+
+    // On test les écritures confiées à un thread dédié
+    #[test]
+    fn test_lru_cache_persistent_background() {
+        println!("=== On effectue un test du thread d'écriture ===");
+
+        let cache_file = "test_cache_background.txt";
+        let log_file = format!("{}.wal", cache_file);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+
+        // Une file d'une seule place : les ajouts attendent le thread sans bloquer
+        let mut cache = PersistentCache::new(100, cache_file);
+        cache.spawn_writer(1).unwrap();
+        for i in 0..150 {
+            cache.put(i, i * 10).unwrap();
+        }
+        cache.sync().unwrap();
+        let reopened: PersistentCache<i32, i32> = PersistentCache::open(100, cache_file).unwrap();
+        assert!(reopened.contains(&149));
+        assert!(!reopened.contains(&49));
+        println!("Les ajouts ont été écrits par le thread, compaction comprise");
+
+        // La destruction du cache termine les écritures en attente
+        for i in 150..160 {
+            cache.put(i, i * 10).unwrap();
+        }
+        drop(cache);
+        let mut reopened: PersistentCache<i32, i32> =
+            PersistentCache::open(100, cache_file).unwrap();
+        assert_eq!(reopened.get(&159), Some(&1590));
+        println!("Les écritures en attente ont été terminées à la destruction");
+
+        // Une erreur d'écriture du thread est retournée par `sync`
+        let mut cache = PersistentCache::new(2, "dossier_inexistant/cache.txt");
+        cache.spawn_writer(4).unwrap();
+        assert!(cache.put(1, 100).is_ok());
+        assert!(matches!(cache.sync(), Err(StorageError::Io(_))));
+        assert!(cache.sync().is_ok());
+        println!("L'erreur du thread a été retournée par sync");
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        println!("=== Fin du test du thread d'écriture ===");
+    }
+
+    // On test le partage d'un même fichier par deux caches, comme deux processus
+    #[test]
+    fn test_lru_cache_persistent_sharing() {
+        println!("=== On effectue un test du partage entre processus ===");
+
+        let cache_file = "test_cache_sharing.txt";
+        let log_file = format!("{}.wal", cache_file);
+        let lock_file = format!("{}.lock", cache_file);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+
+        let mut first: PersistentCache<i32, i32> = PersistentCache::open(10, cache_file).unwrap();
+        let mut second: PersistentCache<i32, i32> = PersistentCache::open(10, cache_file).unwrap();
+        first.set_sharing(Sharing::ReloadOnChange(LoadMode::Replace));
+        second.set_sharing(Sharing::ReloadOnChange(LoadMode::Replace));
+
+        first.put(1, 10).unwrap();
+        second.put(2, 20).unwrap();
+        assert_eq!(second.get(&1), Some(&10));
+        println!("Le second cache a relu l'ajout du premier avant d'écrire");
+
+        second.save().unwrap();
+        first.put(3, 30).unwrap();
+        assert_eq!(first.get(&2), Some(&20));
+        first.save().unwrap();
+        println!("Le premier cache a relu la sauvegarde du second avant d'écrire");
+
+        let mut reopened: PersistentCache<i32, i32> =
+            PersistentCache::open(10, cache_file).unwrap();
+        for key in 1..=3 {
+            assert_eq!(reopened.get(&key), Some(&(key * 10)));
+        }
+        println!("Aucune sauvegarde n'a écrasé celle de l'autre cache");
+
+        // Une écriture attend que le verrou pris par un autre processus soit libéré
+        let lock = fs::File::open(&lock_file).unwrap();
+        lock.lock().unwrap();
+        let (done, finished) = mpsc::channel();
+        let writer = thread::spawn(move || {
+            first.put(4, 40).unwrap();
+            done.send(()).unwrap();
+        });
+        assert!(finished.recv_timeout(Duration::from_millis(100)).is_err());
+        lock.unlock().unwrap();
+        finished.recv_timeout(Duration::from_secs(5)).unwrap();
+        writer.join().unwrap();
+        println!("L'écriture a attendu la libération du verrou");
+
+        drop(second);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        let _ = fs::remove_file(&lock_file);
+        println!("=== Fin du test du partage entre processus ===");
+    }
+
+    // On test la conservation des dernières sauvegardes et la restauration
+    #[test]
+    fn test_lru_cache_persistent_retention() {
+        println!("=== On effectue un test des archives ===");
+
+        let cache_file = "test_cache_retention.txt";
+        let log_file = format!("{}.wal", cache_file);
+        let archive = |name: &str| format!("{}.{}", cache_file, name);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+
+        let mut cache = PersistentCache::new(3, cache_file);
+        cache.set_retention(2, ArchiveNaming::Numbered);
+        for generation in 1..=3 {
+            cache.put(0, generation).unwrap();
+            cache.save().unwrap();
+        }
+        assert_eq!(cache.archives().unwrap(), vec!["1", "2"]);
+        assert!(fs::exists(archive("2")).unwrap());
+        assert!(!fs::exists(archive("3")).unwrap());
+        println!("Seules les 2 dernières sauvegardes ont été conservées");
+
+        // Une sauvegarde de mauvaises données ne fait pas perdre la précédente
+        cache.put(0, -1).unwrap();
+        cache.save().unwrap();
+        cache.restore("2").unwrap();
+        assert_eq!(cache.get(&0), Some(&3));
+        let mut reopened: PersistentCache<i32, i32> = PersistentCache::open(3, cache_file).unwrap();
+        assert_eq!(reopened.get(&0), Some(&3));
+        println!("Le cache a été restauré depuis l'archive 2");
+
+        assert!(matches!(
+            cache.restore("9"),
+            Err(StorageError::Io(err)) if err.kind() == std::io::ErrorKind::NotFound
+        ));
+
+        // Archives datées, dans un stockage en mémoire
+        let mut cache: PersistentCache<String, i32> =
+            PersistentCache::with_store(3, MemoryStore::new(), StringCodec, StringCodec);
+        cache.set_retention(2, ArchiveNaming::Timestamped);
+        for generation in 1..=3 {
+            cache.put("version".to_string(), generation).unwrap();
+            cache.save().unwrap();
+            thread::sleep(Duration::from_millis(2));
+        }
+        let archives = cache.archives().unwrap();
+        println!("Archives datées : {:?}", archives);
+        assert_eq!(archives.len(), 2);
+        assert!(archives.iter().all(|name| name.starts_with('@')));
+        cache.restore(&archives[1]).unwrap();
+        assert_eq!(cache.get(&"version".to_string()), Some(&2));
+        println!("Le cache a été restauré depuis l'archive datée la plus ancienne");
+
+        for name in ["1", "2"] {
+            let _ = fs::remove_file(archive(name));
+        }
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        println!("=== Fin du test des archives ===");
+    }
+
+    // On test l'enregistrement des métadonnées des éléments et des statistiques
+    #[test]
+    fn test_lru_cache_persistent_metadata() {
+        println!("=== On effectue un test des métadonnées ===");
+
+        for format in [SnapshotFormat::Text, SnapshotFormat::Binary] {
+            let store = MemoryStore::new();
+            let mut cache: PersistentCache<String, String> =
+                PersistentCache::with_store(3, store.clone(), StringCodec, StringCodec);
+            cache.set_format(format);
+            let meta = EntryMeta::new()
+                .with_weight(5)
+                .with_tags(&["user:42", "a\nb"]);
+            cache
+                .put_with_meta("A".to_string(), "Valeur A".to_string(), meta.clone())
+                .unwrap();
+            let short = EntryMeta::new().with_ttl(Duration::from_millis(30));
+            cache
+                .put_with_meta("B".to_string(), "Valeur B".to_string(), short)
+                .unwrap();
+            cache.get(&"A".to_string());
+            cache.get(&"A".to_string());
+            cache.get(&"Z".to_string());
+            assert!(cache.contains(&"B".to_string()));
+            cache.save().unwrap();
+            println!(
+                "{:?} : A enregistré avec ses tags, B avec une expiration",
+                format
+            );
+
+            // Les métadonnées et les statistiques sont restaurées
+            let mut reopened: PersistentCache<String, String> =
+                PersistentCache::open_with_store(3, store.clone(), StringCodec, StringCodec)
+                    .unwrap();
+            let restored = reopened.metadata(&"A".to_string()).unwrap();
+            assert_eq!(restored.access_count, 2);
+            assert_eq!(restored.weight, 5);
+            assert_eq!(restored.tags, meta.tags);
+            assert_eq!(restored.inserted_at, meta.inserted_at);
+            let stats = reopened.stats();
+            println!("Statistiques restaurées : {:?}", stats);
+            assert_eq!((stats.hits, stats.misses, stats.insertions), (2, 1, 2));
+
+            // Un élément expiré n'est plus retourné, ni rechargé
+            thread::sleep(Duration::from_millis(40));
+            assert!(!reopened.contains(&"B".to_string()));
+            assert_eq!(reopened.get(&"B".to_string()), None);
+            assert_eq!(reopened.stats().misses, 2);
+            let mut expired: PersistentCache<String, String> =
+                PersistentCache::with_store(3, store, StringCodec, StringCodec);
+            let report = expired.load().unwrap();
+            assert_eq!((report.loaded, report.expired), (1, 1));
+            assert_eq!(report.cache_meta.unwrap().capacity, 3);
+            println!("B a expiré et n'a pas été rechargé");
+        }
+
+        // Les métadonnées passent aussi par le journal
+        let store = MemoryStore::new();
+        let mut cache: PersistentCache<String, String> =
+            PersistentCache::with_store(3, store.clone(), StringCodec, StringCodec);
+        let meta = EntryMeta::new().with_tags(&["journal"]);
+        cache
+            .put_with_meta("C".to_string(), "Valeur C".to_string(), meta)
+            .unwrap();
+        drop(cache);
+        let reopened: PersistentCache<String, String> =
+            PersistentCache::open_with_store(3, store, StringCodec, StringCodec).unwrap();
+        assert_eq!(
+            reopened.metadata(&"C".to_string()).unwrap().tags,
+            vec!["journal".to_string()]
+        );
+        println!("Les tags de C ont été relus depuis le journal");
+
+        // Les tags rechargés permettent d'invalider un groupe, et la capacité
+        // enregistrée est restaurée
+        let store = MemoryStore::new();
+        let mut cache: PersistentCache<String, String> =
+            PersistentCache::with_store(5, store.clone(), StringCodec, StringCodec);
+        for key in ["D", "E"] {
+            let meta = EntryMeta::new().with_tags(&["groupe"]);
+            cache
+                .put_with_meta(key.to_string(), "valeur".to_string(), meta)
+                .unwrap();
+        }
+        cache.put("F".to_string(), "valeur".to_string()).unwrap();
+        cache.save().unwrap();
+        let mut reopened: PersistentCache<String, String> =
+            PersistentCache::open_with_store(2, store, StringCodec, StringCodec).unwrap();
+        assert_eq!(reopened.invalidate_tag("groupe").unwrap(), 2);
+        assert!(!reopened.contains(&"D".to_string()));
+        assert!(reopened.contains(&"F".to_string()));
+        for key in ["G", "H", "I"] {
+            reopened.put(key.to_string(), "valeur".to_string()).unwrap();
+        }
+        assert!(reopened.contains(&"F".to_string()));
+        println!("Le groupe a été invalidé après rechargement, la capacité de 5 est restaurée");
+
+        println!("=== Fin du test des métadonnées ===");
+    }
+
+    // On test la migration des anciens fichiers et leur réécriture
+    #[test]
+    fn test_lru_cache_persistent_upgrade() {
+        println!("=== On effectue un test de la migration des fichiers ===");
+
+        let cache_file = "test_cache_upgrade.txt";
+        let log_file = format!("{}.wal", cache_file);
+        fs::write(cache_file, "A:http://a\nB:value_b\n").unwrap();
+        fs::write(&log_file, "#cache_lru log v1\nP C:value\\:c\nR B\n").unwrap();
+
+        let mut cache: PersistentCache<String, String> =
+            PersistentCache::open(3, cache_file).unwrap();
+        assert_eq!(cache.get(&"A".to_string()), Some(&"http://a".to_string()));
+        assert_eq!(cache.get(&"C".to_string()), Some(&"value:c".to_string()));
+        assert!(!cache.contains(&"B".to_string()));
+        println!("La sauvegarde v1 et le journal v1 ont été migrés au chargement");
+
+        assert!(cache.upgrade_in_place().unwrap());
+        let content = fs::read_to_string(cache_file).unwrap();
+        println!("Sauvegarde réécrite :\n{}", content);
+        assert!(content.starts_with("#cache_lru v3 "));
+        assert!(content.contains("A:http\\://a:"));
+        assert!(fs::read_to_string(&log_file)
+            .unwrap()
+            .starts_with("#cache_lru log v2\n"));
+        assert!(!cache.upgrade_in_place().unwrap());
+        let mut reopened: PersistentCache<String, String> =
+            PersistentCache::open(3, cache_file).unwrap();
+        assert_eq!(reopened.get(&"C".to_string()), Some(&"value:c".to_string()));
+        println!("La sauvegarde est dans la version actuelle");
+
+        // Un journal d'une version inconnue est refusé, même pour une réécriture
+        fs::write(&log_file, "#cache_lru log v9\nP D:d\n").unwrap();
+        assert!(matches!(
+            reopened.upgrade_in_place(),
+            Err(StorageError::UnsupportedVersion {
+                found: 9,
+                supported: 2
+            })
+        ));
+        assert!(PersistentCache::<String, String>::open(3, cache_file).is_err());
+        println!("Le journal d'une version inconnue a été refusé");
+
+        // Une migration propre à l'application relit son ancien format
+        let _ = fs::remove_file(&log_file);
+        fs::write(cache_file, "A=http://a\nB=value_b\n").unwrap();
+        let mut migrations = Migrations::snapshot();
+        migrations.register(1, |line| {
+            let (key, value) = line.split_once('=')?;
+            Some(format::encode_record(key.as_bytes(), value.as_bytes()))
+        });
+        let mut custom: PersistentCache<String, String> = PersistentCache::new(3, cache_file);
+        custom.set_migrations(migrations, Migrations::log());
+        custom.load().unwrap();
+        assert_eq!(custom.get(&"A".to_string()), Some(&"http://a".to_string()));
+        assert_eq!(custom.get(&"B".to_string()), Some(&"value_b".to_string()));
+        println!("L'ancien format de l'application a été migré au chargement");
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        println!("=== Fin du test de la migration des fichiers ===");
+    }
 }
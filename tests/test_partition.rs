@@ -0,0 +1,140 @@
+use cache_lru::partition::PartitionedCache;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // On test qu'un client bruyant n'évince pas les autres clients
+    #[test]
+    fn test_partitioned_cache_fair_share() {
+        println!("=== On effectue un test de partage équitable ===");
+
+        let mut cache = PartitionedCache::new(4);
+        println!("Cache initialisé avec une capacité totale de 4.");
+
+        // Le client A remplit tout le cache
+        for i in 0..4 {
+            cache.put("a", i, i * 10);
+        }
+        assert_eq!(cache.namespace_len(&"a"), 4);
+        println!("Le client A occupe toute la capacité");
+
+        // Le client B récupère sa part en évinçant les éléments de A
+        cache.put("b", 0, 0);
+        cache.put("b", 1, 10);
+        assert_eq!(cache.namespace_len(&"a"), 2);
+        assert_eq!(cache.namespace_len(&"b"), 2);
+        assert_eq!(cache.len(), 4);
+        assert_eq!(cache.get(&"a", &0), None);
+        assert_eq!(cache.get(&"a", &3), Some(&30));
+        println!("Les plus anciens éléments de A ont été évincés");
+
+        // A dépasse à nouveau : B est au-dessus de sa part et perd son plus ancien élément
+        cache.put("b", 2, 20);
+        cache.put("a", 4, 40);
+        assert_eq!(cache.namespace_len(&"a"), 2);
+        assert_eq!(cache.namespace_len(&"b"), 2);
+        assert_eq!(cache.get(&"b", &0), None);
+
+        let stats = cache.stats(&"a");
+        assert_eq!(stats.insertions, 5);
+        assert_eq!(stats.evictions, 3);
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        println!("Statistiques du client A : {:?}", stats);
+
+        println!("=== Fin du test de partage équitable ===");
+    }
+
+    // On test les parts minimales et maximales
+    #[test]
+    fn test_partitioned_cache_quotas() {
+        println!("=== On effectue un test des parts minimales et maximales ===");
+
+        let mut cache = PartitionedCache::new(4);
+        cache.set_quota("a", 0, 2);
+        cache.set_quota("b", 2, 4);
+
+        // A est limité à 2 éléments
+        for i in 0..3 {
+            cache.put("a", i, i);
+        }
+        assert_eq!(cache.namespace_len(&"a"), 2);
+        assert_eq!(cache.get(&"a", &0), None);
+        println!("Le client A est limité à 2 éléments");
+
+        // B garde ses 2 éléments garantis face à C
+        cache.put("b", 0, 0);
+        cache.put("b", 1, 1);
+        for i in 0..4 {
+            cache.put("c", i, i);
+        }
+        assert_eq!(cache.namespace_len(&"b"), 2);
+        assert_eq!(cache.len(), 4);
+        println!("Le client B conserve sa part minimale");
+
+        // Réduire la part maximale évince immédiatement
+        cache.set_quota("b", 0, 1);
+        assert_eq!(cache.namespace_len(&"b"), 1);
+        assert_eq!(cache.get(&"b", &1), Some(&1));
+
+        // On vide un espace de noms
+        let len_c = cache.namespace_len(&"c");
+        assert_eq!(cache.clear_namespace(&"c"), len_c);
+        assert_eq!(cache.namespace_len(&"c"), 0);
+        assert_eq!(
+            cache.len(),
+            cache.namespace_len(&"a") + cache.namespace_len(&"b")
+        );
+        println!("L'espace de noms C a été vidé");
+
+        // Un espace de noms sans part n'accepte aucun élément
+        let len = cache.len();
+        cache.set_quota("z", 0, 0);
+        cache.put("z", 0, 0);
+        assert_eq!(cache.namespace_len(&"z"), 0);
+        assert_eq!(cache.len(), len);
+        println!("L'espace de noms Z, de part maximale nulle, reste vide");
+
+        println!("=== Fin du test des parts minimales et maximales ===");
+    }
+
+    // On test l'ajout lorsque toutes les partitions sont à leur part minimale
+    #[test]
+    fn test_partitioned_cache_min_reserved() {
+        println!("=== On effectue un test des parts minimales réservées ===");
+
+        let mut cache = PartitionedCache::new(4);
+        cache.set_quota("a", 2, 4);
+        cache.set_quota("b", 2, 4);
+        for i in 0..2 {
+            cache.put("a", i, i);
+            cache.put("b", i, i);
+        }
+
+        // Un défaut est compté pour un espace de noms encore inconnu
+        assert_eq!(cache.get(&"d", &0), None);
+        assert_eq!(cache.stats(&"d").misses, 1);
+        assert_eq!(cache.get(&"d", &1), None);
+        assert_eq!(cache.stats(&"d").misses, 2);
+
+        // C n'a aucun élément à céder : son ajout est refusé
+        cache.put("c", 0, 0);
+        assert_eq!(cache.namespace_len(&"c"), 0);
+        assert_eq!(cache.namespace_len(&"b"), 2);
+        assert_eq!(cache.len(), 4);
+        println!("L'ajout dans C est refusé, B conserve sa part minimale");
+
+        // Les défauts de D sont conservés lorsque sa partition est créée
+        cache.put("d", 0, 0);
+        assert_eq!(cache.stats(&"d").misses, 2);
+
+        // A remplace son propre élément le plus ancien
+        cache.put("a", 2, 2);
+        assert_eq!(cache.get(&"a", &0), None);
+        assert_eq!(cache.namespace_len(&"a"), 2);
+        assert_eq!(cache.namespace_len(&"b"), 2);
+        println!("L'ajout dans A remplace son élément le plus ancien");
+
+        println!("=== Fin du test des parts minimales réservées ===");
+    }
+}
@@ -0,0 +1,282 @@
+use cache_lru::refresh::{RefreshCache, RefreshMode};
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // On crée un cache dont le chargement retourne le nombre d'appels au chargement
+    fn counting_cache(
+        ttl: Duration,
+        refresh_after: Duration,
+        delay: Duration,
+    ) -> (RefreshCache<&'static str, usize>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let cache = RefreshCache::new(3, ttl, refresh_after, move |_key: &&str| {
+            thread::sleep(delay);
+            Ok(counter.fetch_add(1, Ordering::SeqCst) + 1)
+        });
+        (cache, calls)
+    }
+
+    // On test le rechargement anticipé en ligne
+    #[test]
+    fn test_refresh_inline() {
+        println!("=== On effectue un test de rechargement en ligne ===");
+
+        let (cache, calls) = counting_cache(
+            Duration::from_millis(500),
+            Duration::from_millis(50),
+            Duration::ZERO,
+        );
+
+        // Le premier accès charge la valeur
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        println!("La clé A a été chargée une seule fois");
+
+        // Passé refresh_after, la lecture retourne la valeur actuelle et recharge
+        thread::sleep(Duration::from_millis(80));
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.get(&"A").unwrap(), 2);
+        println!("La clé A a été rechargée par anticipation");
+
+        // Une valeur expirée est rechargée avant d'être retournée
+        thread::sleep(Duration::from_millis(600));
+        assert_eq!(cache.get(&"A").unwrap(), 3);
+        println!("La clé A expirée a été rechargée");
+
+        println!("=== Fin du test de rechargement en ligne ===");
+    }
+
+    // On test le rechargement anticipé dans un thread
+    #[test]
+    fn test_refresh_background() {
+        println!("=== On effectue un test de rechargement en arrière-plan ===");
+
+        let (mut cache, calls) = counting_cache(
+            Duration::from_millis(200),
+            Duration::from_millis(50),
+            Duration::from_millis(300),
+        );
+        cache.set_mode(RefreshMode::Background);
+        cache.set_grace(Duration::from_millis(100));
+
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+
+        // La lecture déclenche le rechargement sans l'attendre
+        thread::sleep(Duration::from_millis(80));
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+        assert!(cache.is_refreshing(&"A"));
+        println!("Le rechargement de la clé A est en cours");
+
+        // La valeur a expiré pendant le rechargement mais reste servie pendant sa
+        // période de grâce
+        thread::sleep(Duration::from_millis(150));
+        let fetched = cache.fetch(&"A").unwrap();
+        assert_eq!((fetched.value, fetched.stale), (1, true));
+        println!("La clé A est servie pendant son rechargement malgré son expiration");
+
+        // Une fois le rechargement terminé, la nouvelle valeur est servie
+        while cache.is_refreshing(&"A") {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(cache.get(&"A").unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        println!("La clé A a été rechargée en arrière-plan");
+
+        println!("=== Fin du test de rechargement en arrière-plan ===");
+    }
+
+    // On test la période de grâce lorsque la source est indisponible
+    #[test]
+    fn test_stale_while_revalidate() {
+        println!("=== On effectue un test de la période de grâce ===");
+
+        let available = Arc::new(AtomicBool::new(true));
+        let upstream = Arc::clone(&available);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let mut cache = RefreshCache::new(
+            3,
+            Duration::from_millis(50),
+            Duration::from_millis(50),
+            move |_key: &&str| {
+                let call = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                if upstream.load(Ordering::SeqCst) {
+                    Ok(call)
+                } else {
+                    Err(io::Error::other("source indisponible"))
+                }
+            },
+        );
+        cache.set_grace(Duration::from_millis(200));
+
+        let fetched = cache.fetch(&"A").unwrap();
+        assert_eq!((fetched.value, fetched.stale), (1, false));
+
+        // La source tombe : la valeur expirée est servie, marquée comme expirée
+        available.store(false, Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(80));
+        let fetched = cache.fetch(&"A").unwrap();
+        assert_eq!((fetched.value, fetched.stale), (1, true));
+        let fetched = cache.fetch(&"A").unwrap();
+        assert_eq!((fetched.value, fetched.stale), (1, true));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        println!("La clé A expirée est servie malgré l'échec de la revalidation");
+
+        // La source revient : la revalidation réussit
+        available.store(true, Ordering::SeqCst);
+        let fetched = cache.fetch(&"A").unwrap();
+        assert_eq!((fetched.value, fetched.stale), (4, false));
+        println!("La clé A a été revalidée");
+
+        // Passé la période de grâce, l'échec du chargement est retourné
+        available.store(false, Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(300));
+        assert!(cache.fetch(&"A").is_err());
+        println!("La période de grâce de la clé A est terminée");
+
+        println!("=== Fin du test de la période de grâce ===");
+    }
+
+    // On test qu'un rechargement ne remplace pas une valeur ajoutée pendant son exécution
+    #[test]
+    fn test_refresh_superseded() {
+        println!("=== On effectue un test de rechargement concurrent d'un ajout ===");
+
+        let (mut cache, calls) = counting_cache(
+            Duration::from_secs(10),
+            Duration::from_millis(50),
+            Duration::from_millis(200),
+        );
+        cache.set_mode(RefreshMode::Background);
+
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+        assert_eq!(cache.get(&"B").unwrap(), 2);
+        thread::sleep(Duration::from_millis(80));
+        cache.get(&"A").unwrap();
+        cache.get(&"B").unwrap();
+        assert!(cache.is_refreshing(&"A") && cache.is_refreshing(&"B"));
+        println!("Le rechargement des clés A et B est en cours");
+
+        // Ajout et invalidation pendant les rechargements
+        cache.put("A", 100);
+        cache.invalidate(&"B");
+        assert!(!cache.is_refreshing(&"A") && !cache.is_refreshing(&"B"));
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // Les rechargements terminés n'ont rien écrit
+        assert_eq!(cache.get(&"A").unwrap(), 100);
+        assert_eq!(cache.get(&"B").unwrap(), 5);
+        println!("La valeur ajoutée est conservée, la clé invalidée est chargée à nouveau");
+
+        println!("=== Fin du test de rechargement concurrent d'un ajout ===");
+    }
+
+    // On test qu'une fonction de chargement qui panique ne bloque pas la clé
+    #[test]
+    fn test_refresh_panic() {
+        println!("=== On effectue un test de chargement qui panique ===");
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let mut cache = RefreshCache::new(
+            3,
+            Duration::from_millis(100),
+            Duration::from_millis(20),
+            move |_key: &&str| {
+                let call = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                if call == 2 {
+                    panic!("échec du chargement");
+                }
+                Ok(call)
+            },
+        );
+        cache.set_mode(RefreshMode::Background);
+
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+        thread::sleep(Duration::from_millis(40));
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+        // Le thread de rechargement se termine en paniquant
+        let mut waited = 0;
+        while cache.is_refreshing(&"A") && waited < 200 {
+            thread::sleep(Duration::from_millis(10));
+            waited += 1;
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(!cache.is_refreshing(&"A"));
+        println!("Le rechargement qui a paniqué n'est plus en cours");
+
+        // Une fois expirée, la clé est chargée à nouveau
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(cache.get(&"A").unwrap(), 3);
+        println!("La clé A a été chargée à nouveau");
+
+        println!("=== Fin du test de chargement qui panique ===");
+    }
+
+    // On test qu'une clé en cours de rechargement reste servie au-delà de son expiration
+    #[test]
+    fn test_refresh_beyond_ttl() {
+        println!("=== On effectue un test de rechargement trop long ===");
+
+        let (mut cache, calls) = counting_cache(
+            Duration::from_millis(100),
+            Duration::from_millis(20),
+            Duration::from_millis(300),
+        );
+        cache.set_mode(RefreshMode::Background);
+
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+        thread::sleep(Duration::from_millis(40));
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+        assert!(cache.is_refreshing(&"A"));
+        println!("Le rechargement de la clé A est en cours");
+
+        // Sans période de grâce, la valeur expirée est servie sans second chargement
+        thread::sleep(Duration::from_millis(150));
+        let fetched = cache.fetch(&"A").unwrap();
+        assert_eq!((fetched.value, fetched.stale), (1, true));
+        println!("La clé A expirée est servie pendant son rechargement");
+
+        while cache.is_refreshing(&"A") {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let fetched = cache.fetch(&"A").unwrap();
+        assert_eq!((fetched.value, fetched.stale), (2, false));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        println!("La clé A a été rechargée une seule fois");
+
+        println!("=== Fin du test de rechargement trop long ===");
+    }
+
+    // On test qu'une valeur expire même si son rechargement anticipé est plus tardif
+    #[test]
+    fn test_refresh_after_beyond_ttl() {
+        println!("=== On effectue un test de rechargement anticipé après l'expiration ===");
+
+        let (cache, calls) = counting_cache(
+            Duration::from_millis(50),
+            Duration::from_secs(10),
+            Duration::ZERO,
+        );
+
+        assert_eq!(cache.get(&"A").unwrap(), 1);
+        thread::sleep(Duration::from_millis(80));
+        let fetched = cache.fetch(&"A").unwrap();
+        assert_eq!((fetched.value, fetched.stale), (2, false));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        println!("La clé A expirée a été rechargée");
+
+        println!("=== Fin du test de rechargement anticipé après l'expiration ===");
+    }
+}
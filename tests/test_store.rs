@@ -0,0 +1,132 @@
+use cache_lru::storage::binary::crc32;
+use cache_lru::storage::{DirStore, FileStore, MemoryStore, PersistentCache, Store, StringCodec};
+use std::fs;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // On test la réouverture d'un cache sur un stockage en mémoire
+    #[test]
+    fn test_memory_store() {
+        println!("=== On effectue un test du stockage en mémoire ===");
+
+        let store = MemoryStore::new();
+        let mut cache: PersistentCache<String, String> =
+            PersistentCache::with_store(3, store.clone(), StringCodec, StringCodec);
+        cache.put("A".to_string(), "Valeur A".to_string()).unwrap();
+        cache.put("B".to_string(), "Valeur B".to_string()).unwrap();
+        cache.remove(&"A".to_string()).unwrap();
+        println!("Ajouté : A et B, puis A retiré");
+
+        // Le clone partage le contenu : le journal est déjà visible
+        let log = String::from_utf8(store.read_log().unwrap().unwrap()).unwrap();
+        println!("Journal :\n{}", log);
+        assert!(log.contains("P B:Valeur B"));
+        assert!(log.contains("R A"));
+
+        cache.save().unwrap();
+        drop(cache);
+        assert!(store.read_snapshot(0).unwrap().is_some());
+
+        let mut reopened: PersistentCache<String, String> =
+            PersistentCache::open_with_store(3, store, StringCodec, StringCodec).unwrap();
+        assert_eq!(reopened.get(&"A".to_string()), None);
+        assert_eq!(
+            reopened.get(&"B".to_string()),
+            Some(&"Valeur B".to_string())
+        );
+        println!("Le cache a été rouvert depuis la mémoire");
+
+        // Un stockage vide donne un cache vide
+        let empty: PersistentCache<String, String> =
+            PersistentCache::open_with_store(3, MemoryStore::new(), StringCodec, StringCodec)
+                .unwrap();
+        assert!(!empty.contains(&"B".to_string()));
+
+        println!("=== Fin du test du stockage en mémoire ===");
+    }
+
+    // On test un fichier unique désigné par un PathBuf
+    #[test]
+    fn test_file_store_path() {
+        println!("=== On effectue un test du stockage dans un fichier ===");
+
+        let cache_file = PathBuf::from("test_store_path.txt");
+        let _ = fs::remove_file(&cache_file);
+        let _ = fs::remove_file("test_store_path.txt.wal");
+
+        let mut cache: PersistentCache<String, String> = PersistentCache::new(3, &cache_file);
+        cache.put("A".to_string(), "Valeur A".to_string()).unwrap();
+        cache.save().unwrap();
+        drop(cache);
+        assert!(FileStore::new(&cache_file)
+            .read_snapshot(0)
+            .unwrap()
+            .is_some());
+
+        let mut reopened: PersistentCache<String, String> =
+            PersistentCache::open(3, cache_file.as_path()).unwrap();
+        assert_eq!(
+            reopened.get(&"A".to_string()),
+            Some(&"Valeur A".to_string())
+        );
+        println!("Le cache a été rouvert depuis {:?}", cache_file);
+
+        let _ = fs::remove_file(&cache_file);
+        let _ = fs::remove_file("test_store_path.txt.wal");
+        println!("=== Fin du test du stockage dans un fichier ===");
+    }
+
+    // On test la répartition d'un cache entre plusieurs fichiers d'un dossier
+    #[test]
+    fn test_dir_store() {
+        println!("=== On effectue un test du stockage dans un dossier ===");
+
+        let dir = PathBuf::from("test_store_dir");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut cache: PersistentCache<String, String> =
+            PersistentCache::with_store(20, DirStore::new(&dir, 4), StringCodec, StringCodec);
+        for i in 0..8 {
+            cache.put(format!("k{}", i), format!("v{}", i)).unwrap();
+        }
+        cache.save().unwrap();
+        for shard in 0..4 {
+            assert!(dir.join(format!("shard-{}", shard)).exists());
+        }
+        println!("Sauvegarde répartie en 4 fichiers");
+
+        // On retire une partie qui ne contient pas k0 : la compaction suivante ne
+        // doit réécrire que la partie de k0
+        let hot = crc32(b"k0") as usize % 4;
+        let cold = (hot + 1) % 4;
+        let cold_path = dir.join(format!("shard-{}", cold));
+        let cold_content = fs::read(&cold_path).unwrap();
+        fs::remove_file(&cold_path).unwrap();
+
+        cache.set_compaction_threshold(1);
+        cache.put("k0".to_string(), "nouvelle".to_string()).unwrap();
+        assert_eq!(cache.log_len(), 0);
+        assert!(!cold_path.exists());
+        println!("Seule la partie {} a été réécrite", hot);
+        drop(cache);
+
+        fs::write(&cold_path, cold_content).unwrap();
+        let mut reopened: PersistentCache<String, String> =
+            PersistentCache::open_with_store(20, DirStore::new(&dir, 4), StringCodec, StringCodec)
+                .unwrap();
+        assert_eq!(
+            reopened.get(&"k0".to_string()),
+            Some(&"nouvelle".to_string())
+        );
+        for i in 1..8 {
+            assert_eq!(reopened.get(&format!("k{}", i)), Some(&format!("v{}", i)));
+        }
+        println!("Tous les éléments ont été relus depuis le dossier");
+
+        let _ = fs::remove_dir_all(&dir);
+        println!("=== Fin du test du stockage dans un dossier ===");
+    }
+}
@@ -0,0 +1,139 @@
+use cache_lru::storage::PersistentCache;
+use cache_lru::tiered::{TierPolicy, TieredCache};
+use std::fs;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // On test la politique inclusive
+    #[test]
+    fn test_tiered_cache_inclusive() {
+        println!("=== On effectue un test de la politique inclusive ===");
+
+        let cache_file = "test_tiered_inclusive.txt";
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+
+        let l2: PersistentCache<String, String> = PersistentCache::new(10, cache_file);
+        let mut cache = TieredCache::new(2, l2, TierPolicy::Inclusive);
+        println!("Cache initialisé avec 2 éléments en mémoire et 10 sur disque.");
+
+        cache.put("A".to_string(), "value_a".to_string()).unwrap();
+        cache.put("B".to_string(), "value_b".to_string()).unwrap();
+        cache.put("C".to_string(), "value_c".to_string()).unwrap();
+        println!("Ajouté : A, B et C, A a été rétrogradé sur disque");
+
+        // Tous les éléments sont sur disque, seuls les deux derniers en mémoire
+        assert!(cache.l2().contains(&"A".to_string()));
+        assert!(cache.l2().contains(&"C".to_string()));
+        assert!(!cache.l1().contains(&"A".to_string()));
+
+        // A est promu en mémoire et reste sur disque
+        assert_eq!(
+            cache.get(&"A".to_string()).unwrap(),
+            Some(&"value_a".to_string())
+        );
+        assert!(cache.l1().contains(&"A".to_string()));
+        assert!(cache.l2().contains(&"A".to_string()));
+        println!("La clé A a été promue en mémoire");
+
+        assert_eq!(cache.get(&"X".to_string()).unwrap(), None);
+
+        let stats = cache.stats();
+        println!("Statistiques : {:?}", stats);
+        assert_eq!((stats.l1.hits, stats.l1.misses), (0, 2));
+        assert_eq!((stats.l2.hits, stats.l2.misses), (1, 1));
+        assert_eq!(stats.l1.evictions, 2);
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+        println!("=== Fin du test de la politique inclusive ===");
+    }
+
+    // On test la politique exclusive
+    #[test]
+    fn test_tiered_cache_exclusive() {
+        println!("=== On effectue un test de la politique exclusive ===");
+
+        let cache_file = "test_tiered_exclusive.txt";
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+
+        let l2: PersistentCache<i32, i32> = PersistentCache::new(10, cache_file);
+        let mut cache = TieredCache::new(2, l2, TierPolicy::Exclusive);
+
+        cache.put(1, 100).unwrap();
+        cache.put(2, 200).unwrap();
+        assert!(!cache.l2().contains(&1));
+        println!("Les clés 1 et 2 sont uniquement en mémoire");
+
+        // L'éviction rétrograde la clé 1 sur disque
+        cache.put(3, 300).unwrap();
+        assert!(cache.l2().contains(&1));
+        assert!(!cache.l1().contains(&1));
+        println!("La clé 1 a été rétrogradée sur disque");
+
+        // La promotion retire la clé du disque et rétrograde la clé 2
+        assert_eq!(cache.get(&1).unwrap(), Some(&100));
+        assert!(!cache.l2().contains(&1));
+        assert!(cache.l2().contains(&2));
+        println!("La clé 1 a été promue, la clé 2 rétrogradée");
+
+        // La suppression retire la clé des deux niveaux
+        assert_eq!(cache.remove(&2).unwrap(), Some(200));
+        assert_eq!(cache.get(&2).unwrap(), None);
+
+        let stats = cache.stats();
+        assert_eq!(stats.l2.hits, 1);
+        assert_eq!(stats.l1.evictions, 2);
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+        println!("=== Fin du test de la politique exclusive ===");
+    }
+
+    // On test qu'un élément de L1 évincé de L2 est aussi retiré de L1
+    #[test]
+    fn test_tiered_cache_inclusion() {
+        println!("=== On effectue un test de la garantie d'inclusion ===");
+
+        let cache_file = "test_tiered_inclusion.txt";
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+
+        let l2: PersistentCache<i32, i32> = PersistentCache::new(2, cache_file);
+        let mut cache = TieredCache::new(3, l2, TierPolicy::Inclusive);
+        for key in 1..=3 {
+            cache.put(key, key * 100).unwrap();
+        }
+        assert!(!cache.l2().contains(&1));
+        assert!(!cache.l1().contains(&1));
+        assert_eq!(cache.l1().len(), 2);
+        println!("La clé 1, évincée du disque, a été retirée de la mémoire");
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(format!("{}.wal", cache_file));
+        println!("=== Fin du test de la garantie d'inclusion ===");
+    }
+
+    // On test qu'un échec de rétrogradation ne perd pas l'élément évincé
+    #[test]
+    fn test_tiered_cache_demotion_failure() {
+        println!("=== On effectue un test d'échec de rétrogradation ===");
+
+        // Le dossier n'existe pas : toute écriture dans L2 échoue
+        let l2: PersistentCache<i32, i32> =
+            PersistentCache::new(10, "test_tiered_missing_dir/cache.txt");
+        let mut cache = TieredCache::new(1, l2, TierPolicy::Exclusive);
+        cache.put(1, 100).unwrap();
+        assert!(cache.put(2, 200).is_err());
+        println!("La rétrogradation de la clé 1 a échoué");
+
+        assert_eq!(cache.get(&1).unwrap(), Some(&100));
+        assert!(!cache.l1().contains(&2));
+        println!("La clé 1 est toujours en mémoire");
+
+        println!("=== Fin du test d'échec de rétrogradation ===");
+    }
+}
@@ -0,0 +1,197 @@
+use cache_lru::storage::transform::{apply_batch, revert_batch, Transform};
+use cache_lru::storage::{Lz, PersistentCache, StorageError};
+use std::fs;
+
+// Chiffrement de démonstration, pour tester une transformation définie par l'utilisateur
+#[derive(Debug)]
+struct Xor(u8);
+
+impl Transform for Xor {
+    fn name(&self) -> &str {
+        "xor"
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Vec<u8> {
+        bytes.iter().map(|byte| byte ^ self.0).collect()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        Ok(self.encode(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // On test que la compression rend le contenu d'origine
+    #[test]
+    fn test_lz_round_trip() {
+        println!("=== On effectue un test de la compression ===");
+
+        let mut pseudo_random = Vec::new();
+        let mut state: u32 = 7;
+        for _ in 0..5000 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            pseudo_random.push((state >> 16) as u8);
+        }
+        let text: String = (0..500)
+            .map(|i| format!("cle_{}:valeur numéro {}\n", i, i % 7))
+            .collect();
+        let samples = [
+            Vec::new(),
+            b"a".to_vec(),
+            b"abcabcabcabcabcabcabcabcabcabcabc".to_vec(),
+            vec![0; 1000],
+            pseudo_random,
+            text.into_bytes(),
+        ];
+        for sample in &samples {
+            let encoded = Lz.encode(sample);
+            assert_eq!(&Lz.decode(&encoded).unwrap(), sample);
+            println!("{} octets compressés en {}", sample.len(), encoded.len());
+        }
+        let text = &samples[5];
+        assert!(Lz.encode(text).len() < text.len() / 2);
+
+        // Un contenu tronqué est refusé
+        let encoded = Lz.encode(&samples[2]);
+        assert!(Lz.decode(&encoded[..encoded.len() - 1]).is_err());
+
+        println!("=== Fin du test de la compression ===");
+    }
+
+    // On test une sauvegarde compressée puis chiffrée
+    #[test]
+    fn test_transformed_snapshot() {
+        println!("=== On effectue un test des transformations enchaînées ===");
+
+        let cache_file = "test_transform.txt";
+        let log_file = format!("{}.wal", cache_file);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+
+        let mut cache: PersistentCache<String, String> = PersistentCache::new(100, cache_file);
+        cache.add_transform(Lz);
+        cache.add_transform(Xor(0x5A));
+        for i in 0..50 {
+            cache
+                .put(format!("secret_{}", i), "donnée confidentielle".to_string())
+                .unwrap();
+        }
+        cache.save().unwrap();
+
+        let bytes = fs::read(cache_file).unwrap();
+        assert!(bytes.starts_with(b"CLRT\x06\0\0\0lz,xor"));
+        assert!(!String::from_utf8_lossy(&bytes).contains("secret"));
+        println!(
+            "La sauvegarde fait {} octets et ne contient pas les clés",
+            bytes.len()
+        );
+
+        // Les transformations sont retrouvées par leur nom, quel que soit leur ordre
+        let mut reopened: PersistentCache<String, String> = PersistentCache::new(100, cache_file);
+        reopened.add_transform(Xor(0x5A));
+        reopened.add_transform(Lz);
+        reopened.load().unwrap();
+        assert_eq!(
+            reopened.get(&"secret_7".to_string()),
+            Some(&"donnée confidentielle".to_string())
+        );
+        println!("La sauvegarde a été relue");
+
+        // Sans la transformation de chiffrement, le chargement échoue proprement
+        let mut without_key: PersistentCache<String, String> =
+            PersistentCache::new(100, cache_file);
+        without_key.add_transform(Lz);
+        match without_key.load() {
+            Err(StorageError::Transform(reason)) => println!("Erreur attendue : {}", reason),
+            other => panic!("Erreur de transformation attendue, obtenu : {:?}", other),
+        }
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        println!("=== Fin du test des transformations enchaînées ===");
+    }
+
+    // On test que le journal est transformé comme la sauvegarde
+    #[test]
+    fn test_transformed_log() {
+        println!("=== On effectue un test du journal transformé ===");
+
+        let cache_file = "test_transform_log.txt";
+        let log_file = format!("{}.wal", cache_file);
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+
+        let mut cache: PersistentCache<String, String> = PersistentCache::new(100, cache_file);
+        cache.add_transform(Lz);
+        cache.add_transform(Xor(0x5A));
+        for i in 0..10 {
+            cache
+                .put(format!("secret_{}", i), "donnée confidentielle".to_string())
+                .unwrap();
+        }
+        cache.remove(&"secret_3".to_string()).unwrap();
+        drop(cache);
+
+        let log = fs::read_to_string(&log_file).unwrap();
+        assert!(!log.contains("secret"));
+        assert!(log.lines().skip(1).all(|line| line.starts_with("B ")));
+        println!("Le journal ne contient pas les clés");
+
+        // Le journal seul permet de retrouver le contenu du cache
+        let mut reopened: PersistentCache<String, String> = PersistentCache::new(100, cache_file);
+        reopened.add_transform(Lz);
+        reopened.add_transform(Xor(0x5A));
+        reopened.load().unwrap();
+        assert_eq!(
+            reopened.get(&"secret_7".to_string()),
+            Some(&"donnée confidentielle".to_string())
+        );
+        assert!(!reopened.contains(&"secret_3".to_string()));
+        println!("Le journal a été relu");
+
+        // Sans la transformation de chiffrement, le chargement échoue proprement
+        let mut without_key: PersistentCache<String, String> =
+            PersistentCache::new(100, cache_file);
+        without_key.add_transform(Lz);
+        assert!(matches!(
+            without_key.load(),
+            Err(StorageError::Transform(_))
+        ));
+
+        let _ = fs::remove_file(cache_file);
+        let _ = fs::remove_file(&log_file);
+        println!("=== Fin du test du journal transformé ===");
+    }
+
+    // On test qu'un lot compressible du journal est plus court une fois transformé
+    #[test]
+    fn test_transformed_batch_size() {
+        println!("=== On effectue un test de la taille d'un lot transformé ===");
+
+        let transforms: Vec<Box<dyn Transform>> = vec![Box::new(Lz)];
+        let batch: String = (0..100)
+            .map(|i| format!("P session_{}:donnée de session:weight=1\n", i))
+            .collect();
+        let line = apply_batch(&transforms, &batch);
+        println!(
+            "Lot de {} octets écrit sur {} octets",
+            batch.len(),
+            line.len()
+        );
+        assert!(line.len() < batch.len());
+        assert_eq!(revert_batch(&transforms, &line).unwrap(), Some(batch));
+
+        // Les lots de toutes les longueurs sont relus à l'identique
+        for len in 0..8 {
+            let batch = "R clé\n".repeat(len) + &"x".repeat(len);
+            let line = apply_batch(&transforms, &batch);
+            assert_eq!(revert_batch(&transforms, &line).unwrap(), Some(batch));
+        }
+        assert!(revert_batch(&transforms, "B Q0xS*").is_err());
+
+        println!("=== Fin du test de la taille d'un lot transformé ===");
+    }
+}